@@ -4,7 +4,7 @@ use std::fs::File;
 use std::io::BufReader;
 
 fn main() -> Result<(), Error> {
-  let filename = env::args().skip(1).next().unwrap();
+  let filename = env::args().nth(1).unwrap();
   println!("Inspect file: {}", filename);
 
   use mmd::pmx::reader::*;
@@ -61,5 +61,11 @@ fn main() -> Result<(), Error> {
     println!("\n{}) {}", i, j?);
   }
 
+  let mut soft_bodies = SoftBodyReader::<_>::new(joints)?;
+  println!("\n\nSoft bodies:");
+  for (i, s) in soft_bodies.iter::<DefaultConfig>().enumerate() {
+    println!("\n{}) {}", i, s?);
+  }
+
   Ok(())
 }
@@ -0,0 +1,53 @@
+use mmd::{DefaultConfig, Error};
+use std::env;
+use std::fs::File;
+use std::io::BufReader;
+
+fn main() -> Result<(), Error> {
+  let filename = env::args().nth(1).unwrap();
+  println!("Inspect file: {}", filename);
+
+  use mmd::vmd::reader::*;
+
+  let header = HeaderReader::new(BufReader::new(File::open(filename)?))?;
+
+  println!("{}", header);
+
+  let mut bones = BoneKeyframeReader::new(header)?;
+  println!("\nBone keyframes:");
+  for (i, b) in bones.iter::<DefaultConfig>().enumerate() {
+    println!("\n{}) {}", i, b?);
+  }
+
+  let mut morphs = MorphKeyframeReader::new(bones)?;
+  println!("\n\nMorph keyframes:");
+  for (i, m) in morphs.iter().enumerate() {
+    println!("{}) {}", i, m?);
+  }
+
+  let mut cameras = CameraKeyframeReader::new(morphs)?;
+  println!("\n\nCamera keyframes:");
+  for (i, c) in cameras.iter::<DefaultConfig>().enumerate() {
+    println!("\n{}) {}", i, c?);
+  }
+
+  let mut lights = LightKeyframeReader::new(cameras)?;
+  println!("\n\nLight keyframes:");
+  for (i, l) in lights.iter::<DefaultConfig>().enumerate() {
+    println!("{}) {}", i, l?);
+  }
+
+  let mut shadows = ShadowKeyframeReader::new(lights)?;
+  println!("\n\nSelf shadow keyframes:");
+  for (i, s) in shadows.iter().enumerate() {
+    println!("{}) {}", i, s?);
+  }
+
+  let mut iks = IkKeyframeReader::new(shadows)?;
+  println!("\n\nIK keyframes:");
+  for (i, k) in iks.iter().enumerate() {
+    println!("{}) {}", i, k?);
+  }
+
+  Ok(())
+}
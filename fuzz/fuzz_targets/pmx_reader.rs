@@ -0,0 +1,43 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use mmd::pmx::view::ModelView;
+use mmd::reader::{Limits, SectionIndex};
+use std::io::Cursor;
+use mmd::{DefaultConfig, HeaderReader, Model};
+
+// small limits keep the fuzzer from spending its time on huge allocations
+const LIMITS: Limits = Limits {
+  text_length: 1 << 12,
+  section_length: 1 << 12,
+  item_count: 1 << 12,
+};
+
+fuzz_target!(|data: &[u8]| {
+  let _ = Model::<DefaultConfig>::read_with_limits(data, LIMITS);
+  let _ = SectionIndex::scan_with_limits(Cursor::new(data), LIMITS);
+  if let Ok(view) = ModelView::parse_with_limits(data, LIMITS) {
+    let _ = view.vertices().positions();
+    let _ = view.surfaces.to_vec();
+  }
+
+  // walking the typestate chain by hand must not panic either, even after an error
+  if let Ok(header) = HeaderReader::with_limits(data, LIMITS) {
+    let mut vertices = match mmd::VertexReader::new(header) {
+      Ok(v) => v,
+      Err(_) => return,
+    };
+    let _ = vertices.next::<DefaultConfig>();
+    let _ = vertices.next::<DefaultConfig>();
+    let _ = mmd::SurfaceReader::new(vertices)
+      .and_then(mmd::TextureReader::new)
+      .and_then(mmd::MaterialReader::new)
+      .and_then(mmd::BoneReader::new)
+      .and_then(mmd::MorphReader::new)
+      .and_then(mmd::DisplayReader::new)
+      .and_then(mmd::RigidBodyReader::new)
+      .and_then(mmd::JointReader::new)
+      .and_then(mmd::SoftBodyReader::new)
+      .map(|mut soft_bodies| while let Ok(Some(_)) = soft_bodies.next::<DefaultConfig>() {});
+  }
+});
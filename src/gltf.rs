@@ -0,0 +1,521 @@
+use crate::math::{self, mirror};
+use crate::pmx::material::{DrawingFlags, Toon};
+use crate::pmx::morph::Offsets;
+use crate::pmx::skeleton::Skeleton;
+use crate::pmx::types::{raw, resolve, vertex_index};
+use crate::{Config, Error, Model, Result, WeightDeform};
+use byteorder::{WriteBytesExt, LE};
+use serde_json::{json, Value};
+use std::convert::TryFrom;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+const ARRAY_BUFFER: u32 = 34962;
+const ELEMENT_ARRAY_BUFFER: u32 = 34963;
+const UNSIGNED_SHORT: u32 = 5123;
+const UNSIGNED_INT: u32 = 5125;
+const FLOAT: u32 = 5126;
+
+pub fn write_gltf<C: Config, W: Write>(model: &Model<C>, mut write: W) -> Result<W> {
+  let (mut document, buffer) = document(model)?;
+  if !buffer.is_empty() {
+    document["buffers"] = json!([{
+      "byteLength": buffer.len(),
+      "uri": format!("data:application/octet-stream;base64,{}", base64(&buffer)),
+    }]);
+  }
+
+  serde_json::to_writer(&mut write, &document).map_err(std::io::Error::from)?;
+  Ok(write)
+}
+
+pub fn write_glb<C: Config, W: Write>(model: &Model<C>, mut write: W) -> Result<W> {
+  let (mut document, mut buffer) = document(model)?;
+  if !buffer.is_empty() {
+    document["buffers"] = json!([{ "byteLength": buffer.len() }]);
+  }
+
+  let mut json = serde_json::to_vec(&document).map_err(std::io::Error::from)?;
+  pad(&mut json, b' ');
+  pad(&mut buffer, 0);
+
+  // the binary chunk is optional and left out when there is no buffer
+  let binary = if buffer.is_empty() {
+    0
+  } else {
+    8 + buffer.len()
+  };
+  let length = 12 + 8 + json.len() + binary;
+  write.write_all(b"glTF")?;
+  write.write_u32::<LE>(2)?;
+  write.write_u32::<LE>(chunk_length(length)?)?;
+
+  write.write_u32::<LE>(chunk_length(json.len())?)?;
+  write.write_all(b"JSON")?;
+  write.write_all(&json)?;
+
+  if !buffer.is_empty() {
+    write.write_u32::<LE>(chunk_length(buffer.len())?)?;
+    write.write_all(b"BIN\0")?;
+    write.write_all(&buffer)?;
+  }
+
+  Ok(write)
+}
+
+// `.glb` paths get the binary container, anything else a `.gltf` with an embedded buffer
+pub fn save<C: Config, P: AsRef<Path>>(model: &Model<C>, path: P) -> Result<()> {
+  let glb = path
+    .as_ref()
+    .extension()
+    .map_or(false, |e| e.eq_ignore_ascii_case("glb"));
+  let write = BufWriter::new(File::create(path)?);
+
+  let mut write = if glb {
+    write_glb(model, write)?
+  } else {
+    write_gltf(model, write)?
+  };
+  write.flush()?;
+  Ok(())
+}
+
+struct Buffers {
+  data: Vec<u8>,
+  views: Vec<Value>,
+  accessors: Vec<Value>,
+}
+
+impl Buffers {
+  // only vertex attributes and indices may name a target
+  fn view(&mut self, data: &[u8], target: Option<u32>) -> usize {
+    pad(&mut self.data, 0);
+    let mut view = json!({
+      "buffer": 0,
+      "byteOffset": self.data.len(),
+      "byteLength": data.len(),
+    });
+    if let Some(target) = target {
+      view["target"] = json!(target);
+    }
+    self.views.push(view);
+    self.data.extend_from_slice(data);
+    self.views.len() - 1
+  }
+
+  fn accessor(&mut self, mut accessor: Value) -> usize {
+    accessor["bufferView"] = json!(self.views.len() - 1);
+    self.accessors.push(accessor);
+    self.accessors.len() - 1
+  }
+
+  fn floats<const N: usize>(&mut self, values: &[[f32; N]], kind: &str, bounds: bool) -> usize {
+    self.float_view(values, Some(ARRAY_BUFFER), kind, bounds)
+  }
+
+  fn float_view<const N: usize>(
+    &mut self,
+    values: &[[f32; N]],
+    target: Option<u32>,
+    kind: &str,
+    bounds: bool,
+  ) -> usize {
+    let mut data = Vec::with_capacity(values.len() * N * 4);
+    for value in values.iter().flatten() {
+      data.extend_from_slice(&value.to_le_bytes());
+    }
+    self.view(&data, target);
+
+    let mut accessor = json!({
+      "componentType": FLOAT,
+      "count": values.len(),
+      "type": kind,
+    });
+    if bounds && !values.is_empty() {
+      let mut min = [f32::INFINITY; N];
+      let mut max = [f32::NEG_INFINITY; N];
+      for value in values {
+        for i in 0..N {
+          min[i] = min[i].min(value[i]);
+          max[i] = max[i].max(value[i]);
+        }
+      }
+      accessor["min"] = json!(min.to_vec());
+      accessor["max"] = json!(max.to_vec());
+    }
+    self.accessor(accessor)
+  }
+
+  fn joints(&mut self, values: &[[u16; 4]]) -> usize {
+    let mut data = Vec::with_capacity(values.len() * 8);
+    for value in values.iter().flatten() {
+      data.extend_from_slice(&value.to_le_bytes());
+    }
+    self.view(&data, Some(ARRAY_BUFFER));
+    self.accessor(json!({
+      "componentType": UNSIGNED_SHORT,
+      "count": values.len(),
+      "type": "VEC4",
+    }))
+  }
+
+  fn indices(&mut self, values: &[u32]) -> usize {
+    let mut data = Vec::with_capacity(values.len() * 4);
+    for value in values {
+      data.extend_from_slice(&value.to_le_bytes());
+    }
+    self.view(&data, Some(ELEMENT_ARRAY_BUFFER));
+    self.accessor(json!({
+      "componentType": UNSIGNED_INT,
+      "count": values.len(),
+      "type": "SCALAR",
+    }))
+  }
+}
+
+fn document<C: Config>(model: &Model<C>) -> Result<(Value, Vec<u8>)> {
+  let mut buffers = Buffers {
+    data: Vec::new(),
+    views: Vec::new(),
+    accessors: Vec::new(),
+  };
+  let skinned = !model.bones.is_empty();
+  if skinned {
+    Skeleton::new(&model.bones)?;
+  }
+
+  // materials draw consecutive runs of surfaces, runs without triangles get no primitive
+  let mut batches = Vec::new();
+  let mut start = 0;
+  for (i, material) in model.materials.iter().enumerate() {
+    let count = usize::try_from(material.surface_count).unwrap_or(0) / 3;
+    let end = (start + count).min(model.surfaces.len());
+    let mut indices = Vec::with_capacity((end - start) * 3);
+    for [a, b, c] in &model.surfaces[start..end] {
+      for index in &[a, c, b] {
+        let index = vertex_index(*index, model.vertices.len())?;
+        indices.push(u32::try_from(index).map_err(|_| Error::IndexOverflow(index as i64))?);
+      }
+    }
+    start = end;
+
+    if !indices.is_empty() {
+      batches.push((i, indices));
+    }
+  }
+
+  let mut nodes: Vec<Value> = Vec::new();
+  let mut roots = Vec::new();
+  let mut children = vec![Vec::new(); model.bones.len()];
+  for (i, bone) in model.bones.iter().enumerate() {
+    let parent = resolve(&bone.parent, model.bones.len()).filter(|&p| p != i);
+
+    let position = math::vec3(&bone.position);
+    let translation = match parent {
+      Some(p) => {
+        children[p].push(i);
+        math::sub(position, math::vec3(&model.bones[p].position))
+      }
+      None => {
+        roots.push(i);
+        position
+      }
+    };
+
+    nodes.push(json!({
+      "name": bone.local_name,
+      "translation": mirror(translation).to_vec(),
+      "extras": { "universalName": bone.universal_name },
+    }));
+  }
+  for (node, children) in nodes.iter_mut().zip(children) {
+    if !children.is_empty() {
+      node["children"] = json!(children);
+    }
+  }
+
+  // a glTF mesh needs at least one primitive, so a model without triangles keeps only its skeleton
+  let mut meshes = Vec::new();
+  let mut skins = Vec::new();
+  if !batches.is_empty() {
+    let positions: Vec<_> = model
+      .vertices
+      .iter()
+      .map(|v| mirror(math::vec3(&v.position)))
+      .collect();
+    let normals: Vec<_> = model
+      .vertices
+      .iter()
+      .map(|v| mirror(math::vec3(&v.normal)))
+      .collect();
+    let uvs: Vec<_> = model
+      .vertices
+      .iter()
+      .map(|v| {
+        let uv = v.uv.as_ref();
+        [uv[0], uv[1]]
+      })
+      .collect();
+
+    let mut attributes = json!({
+      "POSITION": buffers.floats(&positions, "VEC3", true),
+      "NORMAL": buffers.floats(&normals, "VEC3", false),
+      "TEXCOORD_0": buffers.floats(&uvs, "VEC2", false),
+    });
+
+    if skinned {
+      let mut joints = Vec::with_capacity(model.vertices.len());
+      let mut weights = Vec::with_capacity(model.vertices.len());
+      for vertex in &model.vertices {
+        let (j, w) = influences::<C>(&vertex.weight_deform, model.bones.len())?;
+        joints.push(j);
+        weights.push(w);
+      }
+      attributes["JOINTS_0"] = json!(buffers.joints(&joints));
+      attributes["WEIGHTS_0"] = json!(buffers.floats(&weights, "VEC4", false));
+    }
+
+    let mut target_names = Vec::new();
+    let mut targets = Vec::new();
+    for morph in &model.morphs {
+      if let Offsets::Vertex(offsets) = &morph.offsets {
+        let mut deltas = vec![[0.0; 3]; model.vertices.len()];
+        for offset in offsets {
+          let vertex = vertex_index(&offset.vertex, model.vertices.len())?;
+          deltas[vertex] = math::add(deltas[vertex], mirror(math::vec3(&offset.offset)));
+        }
+        targets.push(json!({ "POSITION": buffers.floats(&deltas, "VEC3", true) }));
+        target_names.push(morph.local_name.clone());
+      }
+    }
+
+    let mut primitives = Vec::new();
+    for (i, indices) in &batches {
+      let mut primitive = json!({
+        "attributes": attributes,
+        "indices": buffers.indices(indices),
+        "material": i,
+      });
+      if !targets.is_empty() {
+        primitive["targets"] = json!(targets);
+      }
+      primitives.push(primitive);
+    }
+
+    let mut mesh = json!({
+      "name": model.model_local_name,
+      "primitives": primitives,
+    });
+    if !targets.is_empty() {
+      mesh["weights"] = json!(vec![0.0; targets.len()]);
+      mesh["extras"] = json!({ "targetNames": target_names });
+    }
+    meshes.push(mesh);
+
+    let mut mesh_node = json!({ "name": model.model_local_name, "mesh": 0 });
+    if skinned {
+      let inverse_binds: Vec<[f32; 16]> = model
+        .bones
+        .iter()
+        .map(|b| {
+          let [x, y, z] = mirror(math::vec3(&b.position));
+          [
+            1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, -x, -y, -z, 1.0,
+          ]
+        })
+        .collect();
+      skins.push(json!({
+        "inverseBindMatrices": buffers.float_view(&inverse_binds, None, "MAT4", false),
+        "joints": (0..model.bones.len()).collect::<Vec<_>>(),
+      }));
+      mesh_node["skin"] = json!(0);
+    }
+    roots.push(nodes.len());
+    nodes.push(mesh_node);
+  }
+
+  let materials: Vec<_> = model
+    .materials
+    .iter()
+    .map(|m| {
+      let diffuse = math::vec4(&m.diffuse_color);
+      let mut pbr = json!({
+        "baseColorFactor": diffuse.to_vec(),
+        "metallicFactor": 0.0,
+        "roughnessFactor": 1.0,
+      });
+      if let Some(texture) = resolve(&m.texture_index, model.textures.len()) {
+        pbr["baseColorTexture"] = json!({ "index": texture });
+      }
+
+      let toon = match &m.toon {
+        Toon::Texture(t) => json!({ "texture": resolve(t, model.textures.len()) }),
+        Toon::Internal(i) => json!({ "internal": i }),
+      };
+
+      json!({
+        "name": m.local_name,
+        "pbrMetallicRoughness": pbr,
+        "doubleSided": m.draw_flags.contains(DrawingFlags::NoCull),
+        "alphaMode": if diffuse[3] < 1.0 { "BLEND" } else { "OPAQUE" },
+        "extras": {
+          "universalName": m.universal_name,
+          "specularColor": math::vec3(&m.specular_color).to_vec(),
+          "specularStrength": m.specular_strength,
+          "ambientColor": math::vec3(&m.ambient_color).to_vec(),
+          "drawFlags": m.draw_flags.bits(),
+          "edgeColor": math::vec4(&m.edge_color).to_vec(),
+          "edgeScale": m.edge_scale,
+          "environmentTexture": resolve(&m.environment_index, model.textures.len()),
+          "environmentBlendMode": m.environment_blend_mode as u8,
+          "toon": toon,
+          "metadata": m.metadata,
+        },
+      })
+    })
+    .collect();
+
+  let images: Vec<_> = model
+    .textures
+    .iter()
+    .map(|t| json!({ "uri": uri(t) }))
+    .collect();
+  let textures: Vec<_> = (0..model.textures.len())
+    .map(|i| json!({ "source": i, "sampler": 0 }))
+    .collect();
+
+  let mut document = json!({
+    "asset": {
+      "version": "2.0",
+      "generator": "mmd-rs",
+      "extras": {
+        "universalName": model.model_universal_name,
+        "comment": model.local_comments,
+        "universalComment": model.universal_comments,
+      },
+    },
+    "scene": 0,
+    "scenes": [{}],
+  });
+  if !roots.is_empty() {
+    document["scenes"][0]["nodes"] = json!(roots);
+  }
+  // glTF rejects empty top level arrays, so lists with nothing in them are left out
+  let lists = [
+    ("nodes", nodes),
+    ("meshes", meshes),
+    ("skins", skins),
+    ("materials", materials),
+    ("bufferViews", buffers.views),
+    ("accessors", buffers.accessors),
+  ];
+  for (key, list) in lists {
+    if !list.is_empty() {
+      document[key] = json!(list);
+    }
+  }
+  if !images.is_empty() {
+    document["images"] = json!(images);
+    document["textures"] = json!(textures);
+    document["samplers"] = json!([{}]);
+  }
+
+  Ok((document, buffers.data))
+}
+
+fn influences<C: Config>(
+  weight_deform: &WeightDeform<C>,
+  bones: usize,
+) -> Result<([u16; 4], [f32; 4])> {
+  let list = match weight_deform {
+    WeightDeform::Bdef1(d) => vec![(&d.bone_index, 1.0)],
+    WeightDeform::Bdef2(d) => vec![
+      (&d.bone_1_index, d.bone_1_weight),
+      (&d.bone_2_index, 1.0 - d.bone_1_weight),
+    ],
+    WeightDeform::Sdef(d) => vec![
+      (&d.bone_1_index, d.bone_1_weight),
+      (&d.bone_2_index, 1.0 - d.bone_1_weight),
+    ],
+    WeightDeform::Bdef4(d) => vec![
+      (&d.bone_1_index, d.bone_1_weight),
+      (&d.bone_2_index, d.bone_2_weight),
+      (&d.bone_3_index, d.bone_3_weight),
+      (&d.bone_4_index, d.bone_4_weight),
+    ],
+    WeightDeform::Qdef(d) => vec![
+      (&d.bone_1_index, d.bone_1_weight),
+      (&d.bone_2_index, d.bone_2_weight),
+      (&d.bone_3_index, d.bone_3_weight),
+      (&d.bone_4_index, d.bone_4_weight),
+    ],
+  };
+
+  let mut joints = [0; 4];
+  let mut weights = [0.0; 4];
+  for (i, (index, weight)) in list.into_iter().enumerate() {
+    if weight <= 0.0 {
+      continue;
+    }
+    joints[i] = resolve(index, bones)
+      .and_then(|i| u16::try_from(i).ok())
+      .ok_or_else(|| Error::InvalidBoneIndex(raw(index)))?;
+    weights[i] = weight;
+  }
+
+  let sum: f32 = weights.iter().sum();
+  if sum > 0.0 {
+    for weight in &mut weights {
+      *weight /= sum;
+    }
+  } else {
+    weights[0] = 1.0;
+  }
+  Ok((joints, weights))
+}
+
+fn uri(path: &str) -> String {
+  let mut uri = String::with_capacity(path.len());
+  for byte in path.replace('\\', "/").bytes() {
+    match byte {
+      b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => {
+        uri.push(byte as char)
+      }
+      _ => uri.push_str(&format!("%{:02X}", byte)),
+    }
+  }
+  uri
+}
+
+fn base64(data: &[u8]) -> String {
+  const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+  let mut encoded = String::with_capacity((data.len() + 2) / 3 * 4);
+  for chunk in data.chunks(3) {
+    let bytes = [
+      chunk[0],
+      *chunk.get(1).unwrap_or(&0),
+      *chunk.get(2).unwrap_or(&0),
+    ];
+    let bits = u32::from(bytes[0]) << 16 | u32::from(bytes[1]) << 8 | u32::from(bytes[2]);
+    for i in 0..4 {
+      if i <= chunk.len() {
+        encoded.push(ALPHABET[(bits >> (18 - i * 6) & 0x3f) as usize] as char);
+      } else {
+        encoded.push('=');
+      }
+    }
+  }
+  encoded
+}
+
+fn pad(data: &mut Vec<u8>, byte: u8) {
+  while data.len() % 4 != 0 {
+    data.push(byte);
+  }
+}
+
+fn chunk_length(length: usize) -> Result<u32> {
+  u32::try_from(length).map_err(|_| Error::IndexOverflow(length as i64))
+}
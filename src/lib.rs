@@ -1,17 +1,31 @@
 #![deny(warnings)]
+#![allow(clippy::should_implement_trait)]
 
+#[cfg(feature = "gltf")]
+pub mod gltf;
+pub mod math;
+pub mod obj;
+pub mod pmd;
 pub mod pmx;
+pub mod vmd;
+pub mod vpd;
 
 pub use self::pmx::bone::Bone;
 pub use self::pmx::error::{Error, Result};
 pub use self::pmx::material::Material;
+pub use self::pmx::model::Model;
 pub use self::pmx::reader::{
   self, BoneReader, DisplayReader, HeaderReader, JointReader, MaterialReader, MorphReader,
-  RigidBodyReader, SurfaceReader, TextureReader, VertexReader,
+  RigidBodyReader, SoftBodyReader, SurfaceReader, TextureReader, VertexReader,
 };
-pub use self::pmx::settings::Settings;
+pub use self::pmx::settings::{ExtraGlobals, Settings};
 pub use self::pmx::types::*;
 pub use self::pmx::vertex::Vertex;
 pub use self::pmx::weight_deform::WeightDeform;
+pub use self::pmx::writer::{
+  self, BoneWriter, DisplayWriter, HeaderWriter, JointWriter, MaterialWriter, MorphWriter,
+  RigidBodyWriter, SoftBodyWriter, SurfaceWriter, TextureWriter, VertexWriter,
+};
 
 mod display;
+mod shift_jis;
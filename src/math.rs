@@ -0,0 +1,250 @@
+pub type Vec3 = [f32; 3];
+pub type Quat = [f32; 4];
+// column major, `m[column][row]`
+pub type Mat4 = [[f32; 4]; 4];
+
+pub const IDENTITY: Mat4 = [
+  [1.0, 0.0, 0.0, 0.0],
+  [0.0, 1.0, 0.0, 0.0],
+  [0.0, 0.0, 1.0, 0.0],
+  [0.0, 0.0, 0.0, 1.0],
+];
+
+pub const QUAT_IDENTITY: Quat = [0.0, 0.0, 0.0, 1.0];
+
+pub fn vec3<V: AsRef<[f32]>>(v: &V) -> Vec3 {
+  let v = v.as_ref();
+  [v[0], v[1], v[2]]
+}
+
+pub fn vec4<V: AsRef<[f32]>>(v: &V) -> [f32; 4] {
+  let v = v.as_ref();
+  [v[0], v[1], v[2], v[3]]
+}
+
+// MMD is left handed while OBJ and glTF are right handed, so z is mirrored on the way in and out,
+// and triangles are flipped along with it to keep their front faces
+pub(crate) fn mirror(v: Vec3) -> Vec3 {
+  [v[0], v[1], -v[2]]
+}
+
+pub fn add(a: Vec3, b: Vec3) -> Vec3 {
+  [a[0] + b[0], a[1] + b[1], a[2] + b[2]]
+}
+
+pub fn sub(a: Vec3, b: Vec3) -> Vec3 {
+  [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+pub fn scale(a: Vec3, s: f32) -> Vec3 {
+  [a[0] * s, a[1] * s, a[2] * s]
+}
+
+pub fn dot(a: Vec3, b: Vec3) -> f32 {
+  a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+pub fn cross(a: Vec3, b: Vec3) -> Vec3 {
+  [
+    a[1] * b[2] - a[2] * b[1],
+    a[2] * b[0] - a[0] * b[2],
+    a[0] * b[1] - a[1] * b[0],
+  ]
+}
+
+pub fn length(a: Vec3) -> f32 {
+  dot(a, a).sqrt()
+}
+
+pub fn normalize(a: Vec3) -> Vec3 {
+  let l = length(a);
+  if l > 0.0 {
+    scale(a, 1.0 / l)
+  } else {
+    a
+  }
+}
+
+pub fn quat_mul(a: Quat, b: Quat) -> Quat {
+  [
+    a[3] * b[0] + a[0] * b[3] + a[1] * b[2] - a[2] * b[1],
+    a[3] * b[1] - a[0] * b[2] + a[1] * b[3] + a[2] * b[0],
+    a[3] * b[2] + a[0] * b[1] - a[1] * b[0] + a[2] * b[3],
+    a[3] * b[3] - a[0] * b[0] - a[1] * b[1] - a[2] * b[2],
+  ]
+}
+
+pub fn quat_conjugate(q: Quat) -> Quat {
+  [-q[0], -q[1], -q[2], q[3]]
+}
+
+pub fn quat_dot(a: Quat, b: Quat) -> f32 {
+  a[0] * b[0] + a[1] * b[1] + a[2] * b[2] + a[3] * b[3]
+}
+
+pub fn quat_normalize(q: Quat) -> Quat {
+  let l = quat_dot(q, q).sqrt();
+  if l > 0.0 {
+    [q[0] / l, q[1] / l, q[2] / l, q[3] / l]
+  } else {
+    QUAT_IDENTITY
+  }
+}
+
+pub fn quat_rotate(q: Quat, v: Vec3) -> Vec3 {
+  let u = [q[0], q[1], q[2]];
+  let t = scale(cross(u, v), 2.0);
+  add(add(v, scale(t, q[3])), cross(u, t))
+}
+
+pub fn quat_slerp(a: Quat, b: Quat, t: f32) -> Quat {
+  let mut cos = quat_dot(a, b);
+  let mut b = b;
+  if cos < 0.0 {
+    cos = -cos;
+    b = [-b[0], -b[1], -b[2], -b[3]];
+  }
+  let (wa, wb) = if cos > 0.9995 {
+    (1.0 - t, t)
+  } else {
+    let angle = cos.acos();
+    let sin = angle.sin();
+    (((1.0 - t) * angle).sin() / sin, (t * angle).sin() / sin)
+  };
+  quat_normalize([
+    a[0] * wa + b[0] * wb,
+    a[1] * wa + b[1] * wb,
+    a[2] * wa + b[2] * wb,
+    a[3] * wa + b[3] * wb,
+  ])
+}
+
+pub fn quat_from_axis_angle(axis: Vec3, angle: f32) -> Quat {
+  let axis = normalize(axis);
+  let (sin, cos) = (angle * 0.5).sin_cos();
+  [axis[0] * sin, axis[1] * sin, axis[2] * sin, cos]
+}
+
+pub fn quat_from_mat4(m: &Mat4) -> Quat {
+  let trace = m[0][0] + m[1][1] + m[2][2];
+  let q = if trace > 0.0 {
+    let s = (trace + 1.0).sqrt() * 2.0;
+    [
+      (m[1][2] - m[2][1]) / s,
+      (m[2][0] - m[0][2]) / s,
+      (m[0][1] - m[1][0]) / s,
+      0.25 * s,
+    ]
+  } else if m[0][0] > m[1][1] && m[0][0] > m[2][2] {
+    let s = (1.0 + m[0][0] - m[1][1] - m[2][2]).sqrt() * 2.0;
+    [
+      0.25 * s,
+      (m[1][0] + m[0][1]) / s,
+      (m[2][0] + m[0][2]) / s,
+      (m[1][2] - m[2][1]) / s,
+    ]
+  } else if m[1][1] > m[2][2] {
+    let s = (1.0 + m[1][1] - m[0][0] - m[2][2]).sqrt() * 2.0;
+    [
+      (m[1][0] + m[0][1]) / s,
+      0.25 * s,
+      (m[2][1] + m[1][2]) / s,
+      (m[2][0] - m[0][2]) / s,
+    ]
+  } else {
+    let s = (1.0 + m[2][2] - m[0][0] - m[1][1]).sqrt() * 2.0;
+    [
+      (m[2][0] + m[0][2]) / s,
+      (m[2][1] + m[1][2]) / s,
+      0.25 * s,
+      (m[0][1] - m[1][0]) / s,
+    ]
+  };
+  quat_normalize(q)
+}
+
+pub fn mat4_from_rotation_translation(q: Quat, t: Vec3) -> Mat4 {
+  let [x, y, z, w] = q;
+  [
+    [
+      1.0 - 2.0 * (y * y + z * z),
+      2.0 * (x * y + z * w),
+      2.0 * (x * z - y * w),
+      0.0,
+    ],
+    [
+      2.0 * (x * y - z * w),
+      1.0 - 2.0 * (x * x + z * z),
+      2.0 * (y * z + x * w),
+      0.0,
+    ],
+    [
+      2.0 * (x * z + y * w),
+      2.0 * (y * z - x * w),
+      1.0 - 2.0 * (x * x + y * y),
+      0.0,
+    ],
+    [t[0], t[1], t[2], 1.0],
+  ]
+}
+
+pub fn mat4_translation(m: &Mat4) -> Vec3 {
+  [m[3][0], m[3][1], m[3][2]]
+}
+
+pub fn mat4_mul(a: &Mat4, b: &Mat4) -> Mat4 {
+  let mut r = [[0.0; 4]; 4];
+  for (c, column) in r.iter_mut().enumerate() {
+    for (row, value) in column.iter_mut().enumerate() {
+      *value = (0..4).map(|k| a[k][row] * b[c][k]).sum();
+    }
+  }
+  r
+}
+
+pub fn mat4_transform_point(m: &Mat4, p: Vec3) -> Vec3 {
+  add(mat4_transform_vector(m, p), mat4_translation(m))
+}
+
+pub fn mat4_transform_vector(m: &Mat4, v: Vec3) -> Vec3 {
+  [
+    m[0][0] * v[0] + m[1][0] * v[1] + m[2][0] * v[2],
+    m[0][1] * v[0] + m[1][1] * v[1] + m[2][1] * v[2],
+    m[0][2] * v[0] + m[1][2] * v[1] + m[2][2] * v[2],
+  ]
+}
+
+// rotation order is x then y then z, `q = qx * qy * qz`
+pub fn quat_from_euler(angles: Vec3) -> Quat {
+  let x = quat_from_axis_angle([1.0, 0.0, 0.0], angles[0]);
+  let y = quat_from_axis_angle([0.0, 1.0, 0.0], angles[1]);
+  let z = quat_from_axis_angle([0.0, 0.0, 1.0], angles[2]);
+  quat_mul(quat_mul(x, y), z)
+}
+
+pub fn quat_to_euler(q: Quat) -> Vec3 {
+  let m = mat4_from_rotation_translation(q, [0.0; 3]);
+  let y = m[2][0].clamp(-1.0, 1.0).asin();
+  if m[2][0].abs() < 0.99999 {
+    [(-m[2][1]).atan2(m[2][2]), y, (-m[1][0]).atan2(m[0][0])]
+  } else {
+    [m[1][2].atan2(m[1][1]), y, 0.0]
+  }
+}
+
+pub fn mat4_inverse_rigid(m: &Mat4) -> Mat4 {
+  let mut r = transpose3(m);
+  let t = mat4_transform_vector(&r, mat4_translation(m));
+  r[3] = [-t[0], -t[1], -t[2], 1.0];
+  r
+}
+
+fn transpose3(m: &Mat4) -> Mat4 {
+  let mut r = IDENTITY;
+  for (c, column) in r.iter_mut().enumerate().take(3) {
+    for (row, value) in column.iter_mut().enumerate().take(3) {
+      *value = m[row][c];
+    }
+  }
+  r
+}
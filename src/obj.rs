@@ -0,0 +1,5 @@
+pub mod reader;
+pub mod writer;
+
+pub use reader::{from_path, read, read_with_mtl};
+pub use writer::{save, write_mtl, write_obj};
@@ -0,0 +1,399 @@
+use crate::math::{self, mirror, Vec3};
+use crate::{
+  pmx::bone::*, pmx::display::*, pmx::material::*, pmx::weight_deform::*, Bone, Config, Error,
+  ExtraGlobals, IndexSize, Material, Model, Result, Settings, TextEncoding, Vertex,
+};
+use encoding::all::UTF_8;
+use encoding::{DecoderTrap, Encoding};
+use enumflags2::BitFlags;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::fs::File;
+use std::io::{BufReader, ErrorKind, Read};
+use std::path::Path;
+
+struct Mtl {
+  diffuse_color: [f32; 3],
+  alpha: f32,
+  specular_color: [f32; 3],
+  specular_strength: f32,
+  ambient_color: Option<[f32; 3]>,
+  texture: Option<String>,
+}
+
+impl Default for Mtl {
+  fn default() -> Self {
+    Mtl {
+      diffuse_color: [1.0; 3],
+      alpha: 1.0,
+      specular_color: [0.0; 3],
+      specular_strength: 5.0,
+      ambient_color: None,
+      texture: None,
+    }
+  }
+}
+
+// position, uv and normal indices of a face corner
+type Corner = (usize, Option<usize>, Option<usize>);
+
+struct Obj {
+  positions: Vec<Vec3>,
+  uvs: Vec<[f32; 2]>,
+  normals: Vec<Vec3>,
+  groups: Vec<(String, Vec<[Corner; 3]>)>,
+  libraries: Vec<String>,
+}
+
+pub fn read<C: Config, R: Read>(read: R) -> Result<Model<C>> {
+  let obj = parse_obj(&decode(read)?)?;
+  build(obj, &HashMap::new(), String::new())
+}
+
+pub fn read_with_mtl<C: Config, R: Read, M: Read>(read: R, mtl: M) -> Result<Model<C>> {
+  let obj = parse_obj(&decode(read)?)?;
+  let mut materials = HashMap::new();
+  parse_mtl(&decode(mtl)?, &mut materials)?;
+  build(obj, &materials, String::new())
+}
+
+// material libraries are looked up next to the `.obj`, missing ones fall back to defaults
+pub fn from_path<C: Config, P: AsRef<Path>>(path: P) -> Result<Model<C>> {
+  let path = path.as_ref();
+  let obj = parse_obj(&decode(BufReader::new(File::open(path)?))?)?;
+
+  let mut materials = HashMap::new();
+  for library in &obj.libraries {
+    let library = path.with_file_name(library);
+    match File::open(library) {
+      Ok(file) => parse_mtl(&decode(BufReader::new(file))?, &mut materials)?,
+      Err(e) if e.kind() == ErrorKind::NotFound => {}
+      Err(e) => return Err(e.into()),
+    }
+  }
+
+  let name = path
+    .file_stem()
+    .map(|s| s.to_string_lossy().into_owned())
+    .unwrap_or_default();
+  build(obj, &materials, name)
+}
+
+fn decode<R: Read>(mut read: R) -> Result<String> {
+  let mut buf = Vec::new();
+  read.read_to_end(&mut buf)?;
+  let buf = buf.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(&buf);
+  UTF_8
+    .decode(buf, DecoderTrap::Strict)
+    .map_err(Error::DecodeText)
+}
+
+fn lines(text: &str) -> impl Iterator<Item = (usize, &str, &str)> {
+  text
+    .lines()
+    .enumerate()
+    .map(|(i, line)| {
+      let line = line
+        .find('#')
+        .map_or(line, |comment| &line[..comment])
+        .trim();
+      let (keyword, rest) = line.split_at(line.find(char::is_whitespace).unwrap_or(line.len()));
+      (i + 1, keyword, rest.trim())
+    })
+    .filter(|(_, keyword, _)| !keyword.is_empty())
+}
+
+fn floats<const N: usize>(line: usize, rest: &str, required: usize) -> Result<[f32; N]> {
+  let mut values = [0.0; N];
+  let mut tokens = rest.split_whitespace();
+  for (i, value) in values.iter_mut().enumerate() {
+    match tokens.next() {
+      Some(token) => *value = token.parse().map_err(|_| Error::InvalidObjSyntax(line))?,
+      None if i >= required => break,
+      None => return Err(Error::InvalidObjSyntax(line)),
+    }
+  }
+  Ok(values)
+}
+
+fn parse_obj(text: &str) -> Result<Obj> {
+  let mut obj = Obj {
+    positions: Vec::new(),
+    uvs: Vec::new(),
+    normals: Vec::new(),
+    groups: Vec::new(),
+    libraries: Vec::new(),
+  };
+  let mut current = None;
+
+  for (line, keyword, rest) in lines(text) {
+    match keyword {
+      "v" => obj.positions.push(mirror(floats(line, rest, 3)?)),
+      "vt" => {
+        let [u, v] = floats(line, rest, 1)?;
+        obj.uvs.push([u, 1.0 - v]);
+      }
+      "vn" => obj.normals.push(mirror(floats(line, rest, 3)?)),
+      "usemtl" => {
+        current = Some(
+          obj
+            .groups
+            .iter()
+            .position(|(name, _)| name == rest)
+            .unwrap_or_else(|| {
+              obj.groups.push((rest.to_owned(), Vec::new()));
+              obj.groups.len() - 1
+            }),
+        );
+      }
+      "mtllib" => obj
+        .libraries
+        .extend(rest.split_whitespace().map(str::to_owned)),
+      "f" => {
+        let corners = rest
+          .split_whitespace()
+          .map(|corner| parse_corner(line, corner, &obj))
+          .collect::<Result<Vec<_>>>()?;
+        if corners.len() < 3 {
+          return Err(Error::InvalidObjSyntax(line));
+        }
+
+        let group = *current.get_or_insert_with(|| {
+          obj.groups.push((String::new(), Vec::new()));
+          obj.groups.len() - 1
+        });
+        // fans are flipped along with the mirrored z axis
+        for i in 1..corners.len() - 1 {
+          obj.groups[group]
+            .1
+            .push([corners[0], corners[i + 1], corners[i]]);
+        }
+      }
+      _ => {}
+    }
+  }
+
+  Ok(obj)
+}
+
+fn parse_corner(line: usize, corner: &str, obj: &Obj) -> Result<Corner> {
+  let mut parts = corner.split('/');
+  let mut index = |len: usize| -> Result<Option<usize>> {
+    match parts.next() {
+      None | Some("") => Ok(None),
+      Some(part) => {
+        let index: i64 = part.parse().map_err(|_| Error::InvalidObjSyntax(line))?;
+        let resolved = if index < 0 {
+          len as i64 + index
+        } else {
+          index - 1
+        };
+        usize::try_from(resolved)
+          .ok()
+          .filter(|&i| i < len)
+          .map(Some)
+          .ok_or(Error::InvalidVertexIndex(index))
+      }
+    }
+  };
+
+  let position = index(obj.positions.len())?.ok_or(Error::InvalidObjSyntax(line))?;
+  let uv = index(obj.uvs.len())?;
+  let normal = index(obj.normals.len())?;
+  Ok((position, uv, normal))
+}
+
+fn parse_mtl(text: &str, materials: &mut HashMap<String, Mtl>) -> Result<()> {
+  let mut current: Option<&mut Mtl> = None;
+
+  for (line, keyword, rest) in lines(text) {
+    if keyword == "newmtl" {
+      current = Some(materials.entry(rest.to_owned()).or_default());
+      continue;
+    }
+
+    let material = match current.as_mut() {
+      Some(material) => material,
+      None => continue,
+    };
+    match keyword {
+      "Kd" => material.diffuse_color = floats(line, rest, 3)?,
+      "Ks" => material.specular_color = floats(line, rest, 3)?,
+      "Ka" => material.ambient_color = Some(floats(line, rest, 3)?),
+      "Ns" => material.specular_strength = floats::<1>(line, rest, 1)?[0],
+      "d" => material.alpha = floats::<1>(line, rest, 1)?[0],
+      "Tr" => material.alpha = 1.0 - floats::<1>(line, rest, 1)?[0],
+      "map_Kd" => {
+        // options come before the file name
+        let texture = if rest.starts_with('-') {
+          rest.split_whitespace().last().unwrap_or_default()
+        } else {
+          rest
+        };
+        material.texture = Some(texture.to_owned());
+      }
+      _ => {}
+    }
+  }
+
+  Ok(())
+}
+
+fn index<I: TryFrom<i32>>(value: usize) -> Result<I> {
+  let value = i32::try_from(value).map_err(|_| Error::IndexOverflow(value as i64))?;
+  I::try_from(value).map_err(|_| Error::IndexOverflow(value.into()))
+}
+
+fn build<C: Config>(obj: Obj, mtl: &HashMap<String, Mtl>, name: String) -> Result<Model<C>> {
+  let mut vertices = Vec::new();
+  let mut surfaces = Vec::new();
+  let mut textures: Vec<String> = Vec::new();
+  let mut materials = Vec::new();
+
+  let mut corners = HashMap::new();
+  let mut corner_list: Vec<Corner> = Vec::new();
+  let mut smooth = vec![[0.0; 3]; obj.positions.len()];
+  let default = Mtl::default();
+
+  for (material_name, faces) in &obj.groups {
+    for face in faces {
+      let [a, b, c] = face.map(|corner| obj.positions[corner.0]);
+      let normal = math::cross(math::sub(b, a), math::sub(c, a));
+
+      let mut surface = [0; 3];
+      for (slot, corner) in surface.iter_mut().zip(face) {
+        if corner.2.is_none() {
+          smooth[corner.0] = math::add(smooth[corner.0], normal);
+        }
+        *slot = *corners.entry(*corner).or_insert_with(|| {
+          corner_list.push(*corner);
+          corner_list.len() - 1
+        });
+      }
+      surfaces.push([index(surface[0])?, index(surface[1])?, index(surface[2])?]);
+    }
+
+    let source = mtl.get(material_name).unwrap_or(&default);
+    let texture_index = match &source.texture {
+      Some(texture) => {
+        let position = textures.iter().position(|t| t == texture);
+        index(position.unwrap_or_else(|| {
+          textures.push(texture.clone());
+          textures.len() - 1
+        }))?
+      }
+      None => C::TextureIndex::try_from(-1).map_err(|_| Error::IndexOverflow(-1))?,
+    };
+    let [r, g, b] = source.diffuse_color;
+    let mut draw_flags: BitFlags<DrawingFlags> = DrawingFlags::GroundShadow
+      | DrawingFlags::DrawShadow
+      | DrawingFlags::ReceiveShadow
+      | DrawingFlags::HasEdge;
+    if source.alpha < 1.0 {
+      draw_flags |= DrawingFlags::NoCull;
+    }
+
+    materials.push(Material {
+      local_name: material_name.clone(),
+      universal_name: material_name.clone(),
+      diffuse_color: [r, g, b, source.alpha].into(),
+      specular_color: source.specular_color.into(),
+      specular_strength: source.specular_strength,
+      ambient_color: source
+        .ambient_color
+        .unwrap_or_else(|| math::scale(source.diffuse_color, 0.5))
+        .into(),
+      draw_flags,
+      edge_color: [0.0, 0.0, 0.0, 1.0].into(),
+      edge_scale: 1.0,
+      texture_index,
+      environment_index: C::TextureIndex::try_from(-1).map_err(|_| Error::IndexOverflow(-1))?,
+      environment_blend_mode: EnvironmentBlendMode::Disabled,
+      toon: Toon::Internal(0),
+      metadata: String::new(),
+      surface_count: i32::try_from(faces.len() * 3)
+        .map_err(|_| Error::IndexOverflow(faces.len() as i64 * 3))?,
+    });
+  }
+
+  for (position, uv, normal) in corner_list {
+    let normal = match normal {
+      Some(normal) => obj.normals[normal],
+      None => math::normalize(smooth[position]),
+    };
+    vertices.push(Vertex {
+      position: obj.positions[position].into(),
+      normal: normal.into(),
+      uv: uv.map_or([0.0; 2], |uv| obj.uvs[uv]).into(),
+      additional: std::iter::empty().collect(),
+      weight_deform: WeightDeform::Bdef1(Bdef1 {
+        bone_index: index(0)?,
+      }),
+      edge_scale: 1.0,
+    });
+  }
+
+  let bones = vec![Bone {
+    local_name: "センター".to_owned(),
+    universal_name: "center".to_owned(),
+    position: [0.0; 3].into(),
+    parent: C::BoneIndex::try_from(-1).map_err(|_| Error::IndexOverflow(-1))?,
+    transform_level: 0,
+    bone_flags: BoneFlags::Rotatable
+      | BoneFlags::Movable
+      | BoneFlags::Display
+      | BoneFlags::CanOperate,
+    connection: Connection::Position([0.0, 1.0, 0.0].into()),
+    additional: None,
+    fixed_axis: None,
+    local_axis: None,
+    external_parent_transform: None,
+    inverse_kinematics: None,
+  }];
+
+  let display_frames = vec![
+    DisplayFrame {
+      local_name: "Root".to_owned(),
+      universal_name: "Root".to_owned(),
+      special_flag: true,
+      frames: vec![Frame::Bone(index(0)?)],
+    },
+    DisplayFrame {
+      local_name: "表情".to_owned(),
+      universal_name: "Exp".to_owned(),
+      special_flag: true,
+      frames: vec![],
+    },
+  ];
+
+  let mut model = Model {
+    version: 2.0,
+    settings: Settings {
+      text_encoding: TextEncoding::UTF16LE,
+      additional_vec4_count: 0,
+      vertex_index_size: IndexSize::I32,
+      texture_index_size: IndexSize::I16,
+      material_index_size: IndexSize::I16,
+      bone_index_size: IndexSize::I16,
+      morph_index_size: IndexSize::I16,
+      rigidbody_index_size: IndexSize::I16,
+      extra_globals: ExtraGlobals::new(),
+    },
+    model_local_name: name.clone(),
+    model_universal_name: name,
+    local_comments: String::new(),
+    universal_comments: String::new(),
+    vertices,
+    surfaces,
+    textures,
+    materials,
+    bones,
+    morphs: vec![],
+    display_frames,
+    rigid_bodies: vec![],
+    joints: vec![],
+    soft_bodies: vec![],
+  };
+  model.settings = model.minimal_settings();
+  Ok(model)
+}
@@ -0,0 +1,121 @@
+use crate::math::{self, mirror};
+use crate::pmx::types::{resolve, vertex_index};
+use crate::{Config, Model, Result};
+use std::collections::HashSet;
+use std::convert::TryFrom;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+// writes the `.obj` next to an `.mtl` with the same file stem
+pub fn save<C: Config, P: AsRef<Path>>(model: &Model<C>, path: P) -> Result<()> {
+  let path = path.as_ref();
+  let mtl = path.with_extension("mtl");
+  let mtl_name = mtl
+    .file_name()
+    .map(|n| n.to_string_lossy().into_owned())
+    .unwrap_or_default();
+
+  write_obj(model, &mtl_name, BufWriter::new(File::create(path)?))?.flush()?;
+  write_mtl(model, BufWriter::new(File::create(&mtl)?))?.flush()?;
+  Ok(())
+}
+
+// positions and normals are mirrored into a right handed space and v starts at the bottom
+pub fn write_obj<C: Config, W: Write>(model: &Model<C>, mtl_name: &str, mut write: W) -> Result<W> {
+  writeln!(write, "# {}", model.model_local_name)?;
+  if !mtl_name.is_empty() {
+    writeln!(write, "mtllib {}", mtl_name)?;
+  }
+
+  for vertex in &model.vertices {
+    let [x, y, z] = mirror(math::vec3(&vertex.position));
+    writeln!(write, "v {} {} {}", x, y, z)?;
+  }
+  for vertex in &model.vertices {
+    let uv = vertex.uv.as_ref();
+    writeln!(write, "vt {} {}", uv[0], 1.0 - uv[1])?;
+  }
+  for vertex in &model.vertices {
+    let [x, y, z] = mirror(math::vec3(&vertex.normal));
+    writeln!(write, "vn {} {} {}", x, y, z)?;
+  }
+
+  let names = material_names(model);
+  let mut start = 0;
+  for (material, name) in model.materials.iter().zip(&names) {
+    let count = usize::try_from(material.surface_count).unwrap_or(0) / 3;
+    let end = (start + count).min(model.surfaces.len());
+
+    writeln!(write, "g {}", name)?;
+    writeln!(write, "usemtl {}", name)?;
+    for [a, b, c] in &model.surfaces[start..end] {
+      write!(write, "f")?;
+      for index in &[a, c, b] {
+        let index = vertex_index(*index, model.vertices.len())? + 1;
+        write!(write, " {}/{}/{}", index, index, index)?;
+      }
+      writeln!(write)?;
+    }
+
+    start = end;
+  }
+
+  Ok(write)
+}
+
+pub fn write_mtl<C: Config, W: Write>(model: &Model<C>, mut write: W) -> Result<W> {
+  for (material, name) in model.materials.iter().zip(material_names(model)) {
+    let diffuse = math::vec4(&material.diffuse_color);
+    let [sr, sg, sb] = math::vec3(&material.specular_color);
+    let [ar, ag, ab] = math::vec3(&material.ambient_color);
+
+    writeln!(write, "newmtl {}", name)?;
+    writeln!(write, "Ka {} {} {}", ar, ag, ab)?;
+    writeln!(write, "Kd {} {} {}", diffuse[0], diffuse[1], diffuse[2])?;
+    writeln!(write, "Ks {} {} {}", sr, sg, sb)?;
+    writeln!(write, "Ns {}", material.specular_strength)?;
+    writeln!(write, "d {}", diffuse[3])?;
+    writeln!(write, "illum 2")?;
+    if let Some(texture) = texture(model, &material.texture_index) {
+      writeln!(write, "map_Kd {}", texture)?;
+    }
+    writeln!(write)?;
+  }
+
+  Ok(write)
+}
+
+// OBJ names end at whitespace and have to be unique to map groups onto materials
+fn material_names<C: Config>(model: &Model<C>) -> Vec<String> {
+  let mut used = HashSet::new();
+
+  model
+    .materials
+    .iter()
+    .enumerate()
+    .map(|(i, material)| {
+      let mut name: String = material
+        .local_name
+        .chars()
+        .map(|c| if c.is_whitespace() { '_' } else { c })
+        .collect();
+      if name.is_empty() {
+        name = format!("material_{}", i);
+      }
+      // a suffixed name can clash with a later material's own name, so count up until it is free
+      let base = name.clone();
+      let mut suffix = i;
+      while !used.insert(name.clone()) {
+        name = format!("{}_{}", base, suffix);
+        suffix += 1;
+      }
+      name
+    })
+    .collect()
+}
+
+fn texture<C: Config>(model: &Model<C>, index: &C::TextureIndex) -> Option<String> {
+  let texture = &model.textures[resolve(index, model.textures.len())?];
+  Some(texture.replace('\\', "/"))
+}
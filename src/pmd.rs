@@ -0,0 +1,3 @@
+pub mod reader;
+
+pub use reader::{from_path, read};
@@ -0,0 +1,637 @@
+use crate::{
+  pmx::bone::*, pmx::display::*, pmx::joint::*, pmx::material::*, pmx::morph::*,
+  pmx::rigid_body::*, pmx::weight_deform::*, shift_jis::ReadShiftJis, Bone, Config, Error,
+  ExtraGlobals, IndexSize, Material, Model, Result, Settings, TextEncoding, Vertex,
+};
+use byteorder::{ReadBytesExt, LE};
+use std::convert::TryFrom;
+use std::fs::File;
+use std::io::{BufReader, ErrorKind, Read};
+use std::path::Path;
+
+const NONE: u16 = 0xFFFF;
+
+struct PmdMaterial {
+  diffuse_color: [f32; 4],
+  specular_strength: f32,
+  specular_color: [f32; 3],
+  ambient_color: [f32; 3],
+  toon_index: u8,
+  edge_flag: u8,
+  surface_count: u32,
+  texture: String,
+}
+
+struct PmdBone {
+  name: String,
+  parent: u16,
+  tail: u16,
+  kind: u8,
+  ik_parent: u16,
+  position: [f32; 3],
+}
+
+struct PmdIK {
+  bone: u16,
+  target: u16,
+  iterations: u16,
+  control_weight: f32,
+  links: Vec<u16>,
+}
+
+struct PmdSkin {
+  kind: u8,
+  name: String,
+  vertices: Vec<(u32, [f32; 3])>,
+}
+
+pub fn read<C: Config, R: Read>(mut read: R) -> Result<Model<C>> {
+  let mut magic = [0u8; 3];
+  read.read_exact(&mut magic)?;
+  if &magic != b"Pmd" {
+    return Err(Error::WrongPmdSignature(magic));
+  }
+
+  // the pmd version has no meaning for the converted model, which is plain pmx 2.0
+  read.read_f32::<LE>()?;
+  let model_local_name = read.read_shift_jis(20)?.trim_end().to_owned();
+  let local_comments = read.read_shift_jis(256)?.trim_end().to_owned();
+
+  let vertex_count = read.read_u32::<LE>()?;
+  let mut vertices = Vec::new();
+  for _ in 0..vertex_count {
+    vertices.push(read_vertex::<C, _>(&mut read)?);
+  }
+
+  let index_count = read.read_u32::<LE>()?;
+  let mut surfaces = Vec::new();
+  for _ in 0..index_count / 3 {
+    surfaces.push([
+      index(read.read_u16::<LE>()?)?,
+      index(read.read_u16::<LE>()?)?,
+      index(read.read_u16::<LE>()?)?,
+    ]);
+  }
+  // a trailing partial triangle is dropped, but still read past to keep later sections aligned
+  for _ in 0..index_count % 3 {
+    read.read_u16::<LE>()?;
+  }
+
+  let material_count = read.read_u32::<LE>()?;
+  let mut pmd_materials = Vec::new();
+  for _ in 0..material_count {
+    pmd_materials.push(PmdMaterial {
+      diffuse_color: read_array(&mut read)?,
+      specular_strength: read.read_f32::<LE>()?,
+      specular_color: read_array(&mut read)?,
+      ambient_color: read_array(&mut read)?,
+      toon_index: read.read_u8()?,
+      edge_flag: read.read_u8()?,
+      surface_count: read.read_u32::<LE>()?,
+      texture: read.read_shift_jis(20)?,
+    });
+  }
+
+  let bone_count = read.read_u16::<LE>()?;
+  let mut pmd_bones = Vec::new();
+  for _ in 0..bone_count {
+    pmd_bones.push(PmdBone {
+      name: read.read_shift_jis(20)?,
+      parent: read.read_u16::<LE>()?,
+      tail: read.read_u16::<LE>()?,
+      kind: read.read_u8()?,
+      ik_parent: read.read_u16::<LE>()?,
+      position: read_array(&mut read)?,
+    });
+  }
+
+  let ik_count = read.read_u16::<LE>()?;
+  let mut pmd_iks = Vec::new();
+  for _ in 0..ik_count {
+    let bone = read.read_u16::<LE>()?;
+    let target = read.read_u16::<LE>()?;
+    let link_count = read.read_u8()?;
+    let iterations = read.read_u16::<LE>()?;
+    let control_weight = read.read_f32::<LE>()?;
+    let links = (0..link_count)
+      .map(|_| read.read_u16::<LE>())
+      .collect::<std::io::Result<_>>()?;
+    pmd_iks.push(PmdIK {
+      bone,
+      target,
+      iterations,
+      control_weight,
+      links,
+    });
+  }
+
+  let skin_count = read.read_u16::<LE>()?;
+  let mut pmd_skins = Vec::new();
+  for _ in 0..skin_count {
+    let name = read.read_shift_jis(20)?;
+    let vertex_count = read.read_u32::<LE>()?;
+    let kind = read.read_u8()?;
+    let mut vertices = Vec::new();
+    for _ in 0..vertex_count {
+      vertices.push((read.read_u32::<LE>()?, read_array(&mut read)?));
+    }
+    pmd_skins.push(PmdSkin {
+      kind,
+      name,
+      vertices,
+    });
+  }
+
+  let skin_display_count = read.read_u8()?;
+  let skin_display = (0..skin_display_count)
+    .map(|_| read.read_u16::<LE>())
+    .collect::<std::io::Result<Vec<_>>>()?;
+
+  let bone_display_name_count = read.read_u8()?;
+  let bone_display_names = (0..bone_display_name_count)
+    .map(|_| Ok(read.read_shift_jis(50)?.trim_end().to_owned()))
+    .collect::<Result<Vec<_>>>()?;
+
+  let bone_display_count = read.read_u32::<LE>()?;
+  let mut bone_display = Vec::new();
+  for _ in 0..bone_display_count {
+    bone_display.push((read.read_u16::<LE>()?, read.read_u8()?));
+  }
+
+  let mut model_universal_name = String::new();
+  let mut universal_comments = String::new();
+  let mut bone_universal_names = vec![String::new(); pmd_bones.len()];
+  let mut skin_universal_names = vec![String::new(); pmd_skins.len()];
+  let mut bone_display_universal_names = vec![String::new(); bone_display_names.len()];
+
+  if optional(read.read_u8())?.unwrap_or(0) != 0 {
+    model_universal_name = read.read_shift_jis(20)?.trim_end().to_owned();
+    universal_comments = read.read_shift_jis(256)?.trim_end().to_owned();
+    for name in &mut bone_universal_names {
+      *name = read.read_shift_jis(20)?;
+    }
+    for name in skin_universal_names.iter_mut().skip(1) {
+      *name = read.read_shift_jis(20)?;
+    }
+    for name in &mut bone_display_universal_names {
+      *name = read.read_shift_jis(50)?.trim_end().to_owned();
+    }
+  }
+
+  let mut toon_textures: Vec<String> = (1..=10).map(default_toon).collect();
+  if let Some(first) = optional(read.read_shift_jis(100))? {
+    toon_textures[0] = first;
+    for toon in toon_textures.iter_mut().skip(1) {
+      *toon = read.read_shift_jis(100)?;
+    }
+  }
+
+  let mut rigid_bodies = Vec::new();
+  let mut joints = Vec::new();
+  if let Some(rigid_body_count) = optional(read.read_u32::<LE>())? {
+    for _ in 0..rigid_body_count {
+      rigid_bodies.push(read_rigid_body::<C, _>(&mut read, &pmd_bones)?);
+    }
+    let joint_count = read.read_u32::<LE>()?;
+    for _ in 0..joint_count {
+      joints.push(read_joint::<C, _>(&mut read)?);
+    }
+  }
+
+  let mut textures = Vec::new();
+  let materials = pmd_materials
+    .into_iter()
+    .enumerate()
+    .map(|(i, material)| convert_material(i, material, &toon_textures, &mut textures))
+    .collect::<Result<Vec<_>>>()?;
+
+  let bones = pmd_bones
+    .iter()
+    .zip(bone_universal_names)
+    .enumerate()
+    .map(|(i, (bone, universal_name))| convert_bone(i, bone, universal_name, &pmd_bones, &pmd_iks))
+    .collect::<Result<Vec<_>>>()?;
+
+  let morphs = convert_morphs(pmd_skins, skin_universal_names)?;
+
+  let mut display_frames = vec![
+    DisplayFrame {
+      local_name: "Root".to_owned(),
+      universal_name: "Root".to_owned(),
+      special_flag: true,
+      frames: if bones.is_empty() {
+        vec![]
+      } else {
+        vec![Frame::Bone(index(0)?)]
+      },
+    },
+    DisplayFrame {
+      local_name: "表情".to_owned(),
+      universal_name: "Exp".to_owned(),
+      special_flag: true,
+      frames: skin_display
+        .iter()
+        .filter(|&&skin| skin > 0)
+        .map(|&skin| Ok(Frame::Morph(index(skin - 1)?)))
+        .collect::<Result<_>>()?,
+    },
+  ];
+  for (local_name, universal_name) in bone_display_names
+    .into_iter()
+    .zip(bone_display_universal_names)
+  {
+    display_frames.push(DisplayFrame {
+      local_name,
+      universal_name,
+      special_flag: false,
+      frames: vec![],
+    });
+  }
+  for (bone, frame) in bone_display {
+    if let Some(display_frame) = display_frames.get_mut(frame as usize + 1) {
+      display_frame.frames.push(Frame::Bone(index(bone)?));
+    }
+  }
+
+  let mut model = Model {
+    version: 2.0,
+    settings: Settings {
+      text_encoding: TextEncoding::UTF16LE,
+      additional_vec4_count: 0,
+      vertex_index_size: IndexSize::I16,
+      texture_index_size: IndexSize::I16,
+      material_index_size: IndexSize::I16,
+      bone_index_size: IndexSize::I16,
+      morph_index_size: IndexSize::I16,
+      rigidbody_index_size: IndexSize::I16,
+      extra_globals: ExtraGlobals::new(),
+    },
+    model_local_name,
+    model_universal_name,
+    local_comments,
+    universal_comments,
+    vertices,
+    surfaces,
+    textures,
+    materials,
+    bones,
+    morphs,
+    display_frames,
+    rigid_bodies,
+    joints,
+    soft_bodies: Vec::new(),
+  };
+  model.settings = model.minimal_settings();
+  Ok(model)
+}
+
+pub fn from_path<C: Config, P: AsRef<Path>>(path: P) -> Result<Model<C>> {
+  read(BufReader::new(File::open(path)?))
+}
+
+fn read_array<R: Read, const N: usize>(read: &mut R) -> Result<[f32; N]> {
+  let mut array = [0.0; N];
+  for v in &mut array {
+    *v = read.read_f32::<LE>()?;
+  }
+  Ok(array)
+}
+
+fn index<I: TryFrom<i32>>(value: u16) -> Result<I> {
+  let value = if value == NONE { -1 } else { value as i32 };
+  I::try_from(value).map_err(|_| Error::IndexOverflow(value.into()))
+}
+
+fn optional<T, E: Into<Error>>(result: std::result::Result<T, E>) -> Result<Option<T>> {
+  match result.map_err(Into::into) {
+    Ok(value) => Ok(Some(value)),
+    Err(Error::Io(e)) if e.kind() == ErrorKind::UnexpectedEof => Ok(None),
+    Err(e) => Err(e),
+  }
+}
+
+fn default_toon(i: usize) -> String {
+  format!("toon{:02}.bmp", i)
+}
+
+fn read_vertex<C: Config, R: Read>(read: &mut R) -> Result<Vertex<C>> {
+  let position = read_array::<_, 3>(read)?.into();
+  let normal = read_array::<_, 3>(read)?.into();
+  let uv = read_array::<_, 2>(read)?.into();
+  let bone_1 = read.read_u16::<LE>()?;
+  let bone_2 = read.read_u16::<LE>()?;
+  let weight = read.read_u8()?;
+  let edge_flag = read.read_u8()?;
+
+  let weight_deform = if bone_1 == bone_2 || weight >= 100 {
+    WeightDeform::Bdef1(Bdef1 {
+      bone_index: index(bone_1)?,
+    })
+  } else if weight == 0 {
+    WeightDeform::Bdef1(Bdef1 {
+      bone_index: index(bone_2)?,
+    })
+  } else {
+    WeightDeform::Bdef2(Bdef2 {
+      bone_1_index: index(bone_1)?,
+      bone_2_index: index(bone_2)?,
+      bone_1_weight: weight as f32 / 100.0,
+    })
+  };
+
+  Ok(Vertex {
+    position,
+    normal,
+    uv,
+    additional: std::iter::empty().collect(),
+    weight_deform,
+    edge_scale: if edge_flag == 0 { 1.0 } else { 0.0 },
+  })
+}
+
+fn read_rigid_body<C: Config, R: Read>(read: &mut R, bones: &[PmdBone]) -> Result<RigidBody<C>> {
+  let local_name = read.read_shift_jis(20)?;
+  let bone = read.read_u16::<LE>()?;
+  let group_id = read.read_u8()?;
+  let collision_mask = read.read_u16::<LE>()?;
+  let shape = ShapeType::try_from(read.read_u8()?)?;
+  let shape_size = read_array::<_, 3>(read)?.into();
+  let offset = read_array::<_, 3>(read)?;
+  let shape_rotation = read_array::<_, 3>(read)?.into();
+
+  // PMD stores the shape relative to its bone, or to the center bone if it has none
+  let origin = bones
+    .get(if bone == NONE { 0 } else { bone as usize })
+    .map_or([0.0; 3], |b| b.position);
+
+  Ok(RigidBody {
+    local_name,
+    universal_name: String::new(),
+    bone_index: index(bone)?,
+    group_id,
+    collision_mask,
+    shape,
+    shape_size,
+    shape_position: [
+      origin[0] + offset[0],
+      origin[1] + offset[1],
+      origin[2] + offset[2],
+    ]
+    .into(),
+    shape_rotation,
+    mass: read.read_f32::<LE>()?,
+    move_attenuation: read.read_f32::<LE>()?,
+    rotation_damping: read.read_f32::<LE>()?,
+    repulsion: read.read_f32::<LE>()?,
+    fiction: read.read_f32::<LE>()?,
+    physics_mode: PhysicsMode::try_from(read.read_u8()?)?,
+  })
+}
+
+fn read_joint<C: Config, R: Read>(read: &mut R) -> Result<Joint<C>> {
+  let rigid_body_index = |value: u32| {
+    i32::try_from(value)
+      .ok()
+      .and_then(|v| C::RigidbodyIndex::try_from(v).ok())
+      .ok_or(Error::IndexOverflow(value.into()))
+  };
+
+  Ok(Joint {
+    local_name: read.read_shift_jis(20)?,
+    universal_name: String::new(),
+    joint_type: JointType::SpringFree,
+    rigid_body_a: rigid_body_index(read.read_u32::<LE>()?)?,
+    rigid_body_b: rigid_body_index(read.read_u32::<LE>()?)?,
+    position: read_array::<_, 3>(read)?.into(),
+    rotation: read_array::<_, 3>(read)?.into(),
+    position_min: read_array::<_, 3>(read)?.into(),
+    position_max: read_array::<_, 3>(read)?.into(),
+    rotation_min: read_array::<_, 3>(read)?.into(),
+    rotation_max: read_array::<_, 3>(read)?.into(),
+    position_spring: read_array::<_, 3>(read)?.into(),
+    rotation_spring: read_array::<_, 3>(read)?.into(),
+  })
+}
+
+fn convert_material<C: Config>(
+  i: usize,
+  material: PmdMaterial,
+  toon_textures: &[String],
+  textures: &mut Vec<String>,
+) -> Result<Material<C>> {
+  let mut add_texture = |name: &str| {
+    let position = textures.iter().position(|t| t == name).unwrap_or_else(|| {
+      textures.push(name.to_owned());
+      textures.len() - 1
+    });
+    C::TextureIndex::try_from(position as i32).map_err(|_| Error::IndexOverflow(position as i64))
+  };
+
+  let mut texture_index = C::TextureIndex::try_from(-1i32).map_err(|_| Error::IndexOverflow(-1))?;
+  let mut environment_index = texture_index.clone();
+  let mut environment_blend_mode = EnvironmentBlendMode::Disabled;
+
+  for name in material.texture.split('*').filter(|name| !name.is_empty()) {
+    let lowercase = name.to_lowercase();
+    if lowercase.ends_with(".sph") {
+      environment_index = add_texture(name)?;
+      environment_blend_mode = EnvironmentBlendMode::Multiply;
+    } else if lowercase.ends_with(".spa") {
+      environment_index = add_texture(name)?;
+      environment_blend_mode = EnvironmentBlendMode::Additive;
+    } else {
+      texture_index = add_texture(name)?;
+    }
+  }
+
+  let toon = match toon_textures.get(material.toon_index as usize) {
+    Some(toon) if *toon == default_toon(material.toon_index as usize + 1) => {
+      Toon::Internal(material.toon_index)
+    }
+    Some(toon) => Toon::Texture(add_texture(toon)?),
+    None => Toon::Texture(C::TextureIndex::try_from(-1i32).map_err(|_| Error::IndexOverflow(-1))?),
+  };
+
+  let mut draw_flags = DrawingFlags::GroundShadow.into();
+  // MMD disables self shadow for materials with the magic alpha 0.98
+  #[allow(clippy::float_cmp)]
+  if material.diffuse_color[3] != 0.98 {
+    draw_flags |= DrawingFlags::DrawShadow | DrawingFlags::ReceiveShadow;
+  }
+  if material.diffuse_color[3] < 1.0 {
+    draw_flags |= DrawingFlags::NoCull;
+  }
+  if material.edge_flag != 0 {
+    draw_flags |= DrawingFlags::HasEdge;
+  }
+
+  Ok(Material {
+    local_name: format!("材質{}", i + 1),
+    universal_name: String::new(),
+    diffuse_color: material.diffuse_color.into(),
+    specular_color: material.specular_color.into(),
+    specular_strength: material.specular_strength,
+    ambient_color: material.ambient_color.into(),
+    draw_flags,
+    edge_color: [0.0, 0.0, 0.0, 1.0].into(),
+    edge_scale: 1.0,
+    texture_index,
+    environment_index,
+    environment_blend_mode,
+    toon,
+    metadata: String::new(),
+    surface_count: material.surface_count as i32,
+  })
+}
+
+fn convert_bone<C: Config>(
+  i: usize,
+  bone: &PmdBone,
+  universal_name: String,
+  bones: &[PmdBone],
+  iks: &[PmdIK],
+) -> Result<Bone<C>> {
+  let mut bone_flags = BoneFlags::Rotatable | BoneFlags::Display | BoneFlags::CanOperate;
+  let mut additional = None;
+  let mut fixed_axis = None;
+
+  match bone.kind {
+    1 | 2 => bone_flags |= BoneFlags::Movable,
+    5 => {
+      bone_flags |= BoneFlags::AddRotation;
+      additional = Some(Additional {
+        parent: index(bone.ik_parent)?,
+        rate: 1.0,
+      });
+    }
+    6 => bone_flags.remove(BoneFlags::Display),
+    7 => bone_flags.remove(BoneFlags::Display | BoneFlags::CanOperate),
+    8 => {
+      if let Some(tail) = bones.get(bone.tail as usize) {
+        let axis = [
+          tail.position[0] - bone.position[0],
+          tail.position[1] - bone.position[1],
+          tail.position[2] - bone.position[2],
+        ];
+        let length = (axis[0] * axis[0] + axis[1] * axis[1] + axis[2] * axis[2]).sqrt();
+        if length > 0.0 {
+          bone_flags |= BoneFlags::FixedAxis;
+          fixed_axis = Some([axis[0] / length, axis[1] / length, axis[2] / length].into());
+        }
+      }
+    }
+    9 => {
+      // the tail field of a rotation-linked bone holds the link rate in percent
+      bone_flags |= BoneFlags::AddRotation;
+      additional = Some(Additional {
+        parent: index(bone.ik_parent)?,
+        rate: bone.tail as f32 / 100.0,
+      });
+    }
+    _ => {}
+  }
+
+  let connection = if bone.kind != 9 && bone.tail != 0 && bone.tail != NONE {
+    bone_flags |= BoneFlags::Connection;
+    Connection::Index(index(bone.tail)?)
+  } else {
+    Connection::Position([0.0; 3].into())
+  };
+
+  let inverse_kinematics = iks
+    .iter()
+    .find(|ik| ik.bone as usize == i)
+    .map(|ik| {
+      Ok::<_, Error>(InverseKinematics {
+        ik_bone: index(ik.target)?,
+        iterations: ik.iterations.into(),
+        limit_angle: ik.control_weight * 4.0,
+        links: ik
+          .links
+          .iter()
+          .map(|&link| {
+            // MMD hardcodes a single axis limit for knees in PMD models
+            let is_knee = bones
+              .get(link as usize)
+              .map_or(false, |b| b.name.contains("ひざ"));
+            Ok(IKLink {
+              ik_bone: index(link)?,
+              limits: if is_knee {
+                Some((
+                  [-std::f32::consts::PI, 0.0, 0.0].into(),
+                  [-0.5f32.to_radians(), 0.0, 0.0].into(),
+                ))
+              } else {
+                None
+              },
+            })
+          })
+          .collect::<Result<_>>()?,
+      })
+    })
+    .transpose()?;
+  if inverse_kinematics.is_some() {
+    bone_flags |= BoneFlags::InverseKinematics;
+  }
+
+  Ok(Bone {
+    local_name: bone.name.clone(),
+    universal_name,
+    position: bone.position.into(),
+    parent: index(bone.parent)?,
+    transform_level: 0,
+    bone_flags,
+    connection,
+    additional,
+    fixed_axis,
+    local_axis: None,
+    external_parent_transform: None,
+    inverse_kinematics,
+  })
+}
+
+fn convert_morphs<C: Config>(
+  skins: Vec<PmdSkin>,
+  universal_names: Vec<String>,
+) -> Result<Vec<Morph<C>>> {
+  let mut skins = skins.into_iter().zip(universal_names);
+  let base = match skins.next() {
+    Some((base, _)) => base,
+    None => return Ok(vec![]),
+  };
+
+  skins
+    .map(|(skin, universal_name)| {
+      let offsets = skin
+        .vertices
+        .iter()
+        .map(|&(i, offset)| {
+          let &(vertex, _) = base
+            .vertices
+            .get(i as usize)
+            .ok_or(Error::IndexOverflow(i.into()))?;
+          Ok(VertexOffset {
+            vertex: i32::try_from(vertex)
+              .ok()
+              .and_then(|v| C::VertexIndex::try_from(v).ok())
+              .ok_or(Error::IndexOverflow(vertex.into()))?,
+            offset: offset.into(),
+          })
+        })
+        .collect::<Result<_>>()?;
+
+      Ok(Morph {
+        local_name: skin.name,
+        universal_name,
+        panel: match skin.kind {
+          1 => Panel::Eyebrows,
+          2 => Panel::Eyes,
+          3 => Panel::Mouth,
+          4 => Panel::Other,
+          kind => Panel::Unknown(kind),
+        },
+        offsets: Offsets::Vertex(offsets),
+      })
+    })
+    .collect()
+}
@@ -1,12 +1,22 @@
 pub mod bone;
 pub mod display;
 pub mod error;
+mod ik;
 pub mod joint;
 pub mod material;
+pub mod model;
 pub mod morph;
+pub mod morphing;
 pub mod reader;
+mod references;
 pub mod rigid_body;
 pub mod settings;
+pub mod skeleton;
+pub mod skinning;
+pub mod soft_body;
 pub mod types;
+pub mod validate;
 pub mod vertex;
+pub mod view;
 pub mod weight_deform;
+pub mod writer;
@@ -3,9 +3,12 @@ use itertools::Itertools;
 use std::fmt::{Debug, Display, Formatter};
 
 use crate::{display::DisplayOption, Config};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
 #[derive(BitFlags, Copy, Clone, PartialEq, Debug)]
 #[repr(u16)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum BoneFlags {
   Connection = 0b0000_0000_0000_0001,
   Rotatable = 0b0000_0000_0000_0010,
@@ -32,6 +35,14 @@ impl Display for BoneFlagsFmt {
 }
 
 #[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(Serialize, Deserialize),
+  serde(bound(
+    serialize = "C::BoneIndex: Serialize, C::Vec3: Serialize",
+    deserialize = "C::BoneIndex: Deserialize<'de>, C::Vec3: Deserialize<'de>"
+  ))
+)]
 pub enum Connection<C: Config> {
   Index(C::BoneIndex),
   Position(C::Vec3),
@@ -51,6 +62,14 @@ where
 }
 
 #[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(Serialize, Deserialize),
+  serde(bound(
+    serialize = "C::BoneIndex: Serialize",
+    deserialize = "C::BoneIndex: Deserialize<'de>"
+  ))
+)]
 pub struct Additional<C: Config> {
   pub parent: C::BoneIndex,
   pub rate: f32,
@@ -66,6 +85,14 @@ where
 }
 
 #[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(Serialize, Deserialize),
+  serde(bound(
+    serialize = "C::Vec3: Serialize",
+    deserialize = "C::Vec3: Deserialize<'de>"
+  ))
+)]
 pub struct LocalAxis<C: Config> {
   pub x: C::Vec3,
   pub z: C::Vec3,
@@ -81,6 +108,14 @@ where
 }
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(Serialize, Deserialize),
+  serde(bound(
+    serialize = "C::BoneIndex: Serialize, C::Vec3: Serialize",
+    deserialize = "C::BoneIndex: Deserialize<'de>, C::Vec3: Deserialize<'de>"
+  ))
+)]
 pub struct InverseKinematics<C: Config> {
   pub ik_bone: C::BoneIndex,
   pub iterations: u32,
@@ -106,6 +141,14 @@ where
 }
 
 #[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(Serialize, Deserialize),
+  serde(bound(
+    serialize = "C::BoneIndex: Serialize, C::Vec3: Serialize",
+    deserialize = "C::BoneIndex: Deserialize<'de>, C::Vec3: Deserialize<'de>"
+  ))
+)]
 pub struct IKLink<C: Config> {
   pub ik_bone: C::BoneIndex,
   pub limits: Option<(C::Vec3, C::Vec3)>,
@@ -126,6 +169,14 @@ where
   }
 }
 
+#[cfg_attr(
+  feature = "serde",
+  derive(Serialize, Deserialize),
+  serde(bound(
+    serialize = "C::BoneIndex: Serialize, C::Vec3: Serialize",
+    deserialize = "C::BoneIndex: Deserialize<'de>, C::Vec3: Deserialize<'de>"
+  ))
+)]
 pub struct Bone<C: Config> {
   pub local_name: String,
   pub universal_name: String,
@@ -1,8 +1,18 @@
 use crate::Config;
 use itertools::Itertools;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 use std::fmt::{Debug, Display, Formatter};
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(Serialize, Deserialize),
+  serde(bound(
+    serialize = "C::BoneIndex: Serialize, C::MorphIndex: Serialize",
+    deserialize = "C::BoneIndex: Deserialize<'de>, C::MorphIndex: Deserialize<'de>"
+  ))
+)]
 pub enum Frame<C: Config> {
   Bone(C::BoneIndex),
   Morph(C::MorphIndex),
@@ -21,6 +31,14 @@ where
   }
 }
 
+#[cfg_attr(
+  feature = "serde",
+  derive(Serialize, Deserialize),
+  serde(bound(
+    serialize = "C::BoneIndex: Serialize, C::MorphIndex: Serialize",
+    deserialize = "C::BoneIndex: Deserialize<'de>, C::MorphIndex: Deserialize<'de>"
+  ))
+)]
 pub struct DisplayFrame<C: Config> {
   pub local_name: String,
   pub universal_name: String,
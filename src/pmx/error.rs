@@ -1,5 +1,9 @@
+// err-derive expands its impls inside an anonymous const
+#![allow(non_local_definitions)]
+
 use err_derive::Error;
 use std::borrow::Cow;
+use std::fmt::{Display, Formatter};
 
 #[derive(Debug, Error)]
 pub enum Error {
@@ -7,6 +11,12 @@ pub enum Error {
   Io(#[error(source)] std::io::Error),
   #[error(display = "Wrong signature {:?}", _0)]
   WrongSignature([u8; 4]),
+  #[error(display = "Wrong PMD signature {:?}", _0)]
+  WrongPmdSignature([u8; 3]),
+  #[error(display = "Wrong VMD signature {:?}", _0)]
+  WrongVmdSignature([u8; 30]),
+  #[error(display = "Wrong VPD signature {}", _0)]
+  WrongVpdSignature(String),
   #[error(display = "Globals count less than 8 {}", _0)]
   GlobalsCountLessThan8(u8),
   #[error(display = "Unknown index size {}", _0)]
@@ -35,6 +45,152 @@ pub enum Error {
   InvalidPhysicsMode(u8),
   #[error(display = "Invalid joint type {}", _0)]
   InvalidJointType(u8),
+  #[error(display = "Invalid soft body shape type {}", _0)]
+  InvalidSoftBodyShape(u8),
+  #[error(display = "Invalid soft body aero model {}", _0)]
+  InvalidAeroModel(i32),
+  #[error(display = "Invalid self shadow mode {}", _0)]
+  InvalidShadowMode(u8),
+  #[error(display = "Invalid VPD syntax at line {}", _0)]
+  InvalidVpdSyntax(usize),
+  #[error(display = "Invalid OBJ syntax at line {}", _0)]
+  InvalidObjSyntax(usize),
+  #[error(display = "Invalid bone index {}", _0)]
+  InvalidBoneIndex(i64),
+  #[error(display = "Bone {} has invalid parent {}", _0, _1)]
+  InvalidBoneParent(usize, i64),
+  #[error(display = "Bone {} is part of a parent cycle", _0)]
+  BoneCycle(usize),
+  #[error(display = "Invalid vertex index {}", _0)]
+  InvalidVertexIndex(i64),
+  #[error(display = "Invalid material index {}", _0)]
+  InvalidMaterialIndex(i64),
+  #[error(display = "Invalid morph index {}", _0)]
+  InvalidMorphIndex(i64),
+  #[error(display = "Invalid rigid body index {}", _0)]
+  InvalidRigidBodyIndex(i64),
+  #[error(display = "Morph {} is part of a group cycle", _0)]
+  MorphCycle(usize),
+  #[error(display = "Encode text {}", _0)]
+  EncodeText(Cow<'static, str>),
+  #[error(display = "Wrong additional vec4 count {}", _0)]
+  WrongAdditionalVec4Count(usize),
+  #[error(display = "Bone flag {:?} does not match bone data", _0)]
+  BoneFlagsMismatch(crate::pmx::bone::BoneFlags),
+  #[error(display = "Section element count exceeded {}", _0)]
+  SectionOverflow(i32),
+  #[error(display = "Section has {} unwritten elements", _0)]
+  SectionIncomplete(i32),
+  #[error(display = "{} #{} refers to {} {} out of range", _0, _1, _2, _3)]
+  IndexOutOfRange(Section, usize, Section, i64),
+  #[error(display = "Unknown bone flag bits {:#06x}", _0)]
+  UnknownBoneFlags(u16),
+  #[error(display = "Unknown drawing flag bits {:#04x}", _0)]
+  UnknownDrawingFlags(u8),
+  #[error(display = "Unknown soft body flag bits {:#04x}", _0)]
+  UnknownSoftBodyFlags(u8),
+  #[error(display = "Unsupported version {:?}", _0)]
+  UnsupportedVersion(f32),
+  #[error(display = "Negative length {}", _0)]
+  NegativeLength(i32),
+  #[error(display = "Length {} exceeds the limit of {}", _0, _1)]
+  LimitExceeded(u64, usize),
+  #[error(display = "{:?} section has no element {}", _0, _1)]
+  InvalidElementIndex(Section, usize),
+  #[error(display = "Poisoned by an earlier error")]
+  Poisoned,
+  #[error(display = "{} in {}", _1, _0)]
+  Located(Box<Location>, #[error(source)] Box<Error>),
+}
+
+impl Error {
+  pub fn location(&self) -> Option<&Location> {
+    match self {
+      Error::Located(location, _) => Some(location),
+      _ => None,
+    }
+  }
+
+  // the error without the location it was raised at
+  pub fn kind(&self) -> &Error {
+    match self {
+      Error::Located(_, error) => error.kind(),
+      error => error,
+    }
+  }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Section {
+  Header,
+  Vertices,
+  Surfaces,
+  Textures,
+  Materials,
+  Bones,
+  Morphs,
+  DisplayFrames,
+  RigidBodies,
+  Joints,
+  SoftBodies,
+}
+
+impl Section {
+  fn element(self) -> &'static str {
+    match self {
+      Section::Header => "header",
+      Section::Vertices => "vertex",
+      Section::Surfaces => "surface",
+      Section::Textures => "texture",
+      Section::Materials => "material",
+      Section::Bones => "bone",
+      Section::Morphs => "morph",
+      Section::DisplayFrames => "display frame",
+      Section::RigidBodies => "rigid body",
+      Section::Joints => "joint",
+      Section::SoftBodies => "soft body",
+    }
+  }
+
+  fn item(self) -> &'static str {
+    match self {
+      Section::Bones => "IK link",
+      Section::Morphs => "offset",
+      Section::DisplayFrames => "frame",
+      _ => "item",
+    }
+  }
+}
+
+impl Display for Section {
+  fn fmt(&self, f: &mut Formatter) -> std::result::Result<(), std::fmt::Error> {
+    write!(f, "{}", self.element())
+  }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Location {
+  pub offset: u64,
+  pub section: Section,
+  pub element: Option<usize>,
+  pub name: Option<String>,
+  pub item: Option<usize>,
+}
+
+impl Display for Location {
+  fn fmt(&self, f: &mut Formatter) -> std::result::Result<(), std::fmt::Error> {
+    match self.element {
+      Some(element) => write!(f, "{} #{}", self.section.element(), element)?,
+      None => write!(f, "{} section", self.section.element())?,
+    }
+    if let Some(name) = &self.name {
+      write!(f, " '{}'", name)?;
+    }
+    if let Some(item) = self.item {
+      write!(f, ", {} #{}", self.section.item(), item)?;
+    }
+    write!(f, " at byte {}", self.offset)
+  }
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
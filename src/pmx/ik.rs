@@ -0,0 +1,148 @@
+use crate::math::{self, Mat4, Vec3};
+use crate::pmx::skeleton::{Skeleton, Transform};
+
+const EPSILON: f32 = 1.0e-5;
+
+#[derive(Clone, Debug)]
+pub(crate) struct Chain {
+  pub(crate) target: usize,
+  pub(crate) iterations: u32,
+  pub(crate) limit_angle: f32,
+  pub(crate) links: Vec<Link>,
+  // from the outermost link down to the target
+  pub(crate) path: Vec<usize>,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct Link {
+  pub(crate) bone: usize,
+  pub(crate) limits: Option<(Vec3, Vec3)>,
+}
+
+pub(crate) fn solve(
+  skeleton: &Skeleton,
+  bone: usize,
+  chain: &Chain,
+  locals: &mut [Transform],
+  appended: &mut [Transform],
+  world: &mut [Mat4],
+) {
+  let goal = math::mat4_translation(&world[bone]);
+
+  for iteration in 0..chain.iterations {
+    for link in &chain.links {
+      rotate_link(chain, link, iteration, goal, locals, world);
+
+      let start = chain.path.iter().position(|&b| b == link.bone).unwrap_or(0);
+      for &b in &chain.path[start..] {
+        skeleton.update(b, locals, appended, world);
+      }
+    }
+
+    let target = math::mat4_translation(&world[chain.target]);
+    if math::length(math::sub(target, goal)) < EPSILON {
+      break;
+    }
+  }
+}
+
+fn rotate_link(
+  chain: &Chain,
+  link: &Link,
+  iteration: u32,
+  goal: Vec3,
+  locals: &mut [Transform],
+  world: &[Mat4],
+) {
+  let inverse = math::mat4_inverse_rigid(&world[link.bone]);
+  let target = math::mat4_transform_point(&inverse, math::mat4_translation(&world[chain.target]));
+  let goal = math::mat4_transform_point(&inverse, goal);
+  let local = &mut locals[link.bone];
+
+  if let Some((lower, upper)) = link.limits {
+    if let Some(axis) = single_axis(lower, upper) {
+      let rotation = rotate_plane(chain, link, axis, iteration, target, goal, local.rotation);
+      let angle =
+        (rotation + math::quat_to_euler(local.rotation)[axis]).clamp(lower[axis], upper[axis]);
+      let mut unit = [0.0; 3];
+      unit[axis] = 1.0;
+      local.rotation = math::quat_from_axis_angle(unit, angle);
+      return;
+    }
+  }
+
+  let target = math::normalize(target);
+  let goal = math::normalize(goal);
+  let angle = clamp_step(chain, math::dot(target, goal).clamp(-1.0, 1.0).acos());
+  let axis = math::cross(target, goal);
+  if angle < EPSILON || math::length(axis) < EPSILON {
+    return;
+  }
+
+  let rotation = math::quat_mul(local.rotation, math::quat_from_axis_angle(axis, angle));
+  local.rotation = math::quat_normalize(rotation);
+
+  if let Some((lower, upper)) = link.limits {
+    let mut angles = math::quat_to_euler(local.rotation);
+    for (i, angle) in angles.iter_mut().enumerate() {
+      *angle = angle.clamp(lower[i], upper[i]);
+    }
+    local.rotation = math::quat_from_euler(angles);
+  }
+}
+
+// knee style links rotate around a single axis, so the step is solved in the plane of that axis
+fn rotate_plane(
+  chain: &Chain,
+  link: &Link,
+  axis: usize,
+  iteration: u32,
+  target: Vec3,
+  goal: Vec3,
+  rotation: math::Quat,
+) -> f32 {
+  let mut unit = [0.0; 3];
+  unit[axis] = 1.0;
+
+  let project = |v: Vec3| math::normalize(math::sub(v, math::scale(unit, math::dot(v, unit))));
+  let (projected_target, projected_goal) = (project(target), project(goal));
+
+  let angle = math::dot(unit, math::cross(projected_target, projected_goal))
+    .atan2(math::dot(projected_target, projected_goal));
+
+  // a fully stretched chain can not bend by itself, so the first step bends it towards its limits
+  if iteration == 0
+    && angle.abs() < EPSILON
+    && math::length(math::sub(target, goal)) > EPSILON
+    && math::quat_to_euler(rotation)[axis].abs() < EPSILON
+  {
+    let step = clamp_step(chain, std::f32::consts::FRAC_PI_4);
+    return match link.limits {
+      Some((lower, upper)) if lower[axis].abs() > upper[axis].abs() => -step,
+      _ => step,
+    };
+  }
+
+  let step = clamp_step(chain, angle.abs());
+  if angle < 0.0 {
+    -step
+  } else {
+    step
+  }
+}
+
+fn clamp_step(chain: &Chain, angle: f32) -> f32 {
+  if chain.limit_angle > 0.0 {
+    angle.min(chain.limit_angle)
+  } else {
+    angle
+  }
+}
+
+fn single_axis(lower: Vec3, upper: Vec3) -> Option<usize> {
+  let mut free = (0..3).filter(|&i| lower[i] != 0.0 || upper[i] != 0.0);
+  match (free.next(), free.next()) {
+    (Some(axis), None) => Some(axis),
+    _ => None,
+  }
+}
@@ -1,9 +1,12 @@
 use crate::{Config, Error};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 use std::convert::TryFrom;
 use std::fmt::{Debug, Display, Formatter};
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 #[repr(u8)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum JointType {
   SpringFree = 0,
   Free = 1,
@@ -42,6 +45,14 @@ impl TryFrom<u8> for JointType {
   }
 }
 
+#[cfg_attr(
+  feature = "serde",
+  derive(Serialize, Deserialize),
+  serde(bound(
+    serialize = "C::RigidbodyIndex: Serialize, C::Vec3: Serialize",
+    deserialize = "C::RigidbodyIndex: Deserialize<'de>, C::Vec3: Deserialize<'de>"
+  ))
+)]
 pub struct Joint<C: Config> {
   pub local_name: String,
   pub universal_name: String,
@@ -0,0 +1,242 @@
+use crate::{
+  pmx::display::DisplayFrame, pmx::joint::Joint, pmx::morph::Morph, pmx::rigid_body::RigidBody,
+  pmx::soft_body::SoftBody, reader::*, writer::*, Bone, Config, Error, IndexSize, Material, Result,
+  Settings, Vertex,
+};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+#[cfg_attr(
+  feature = "serde",
+  derive(Serialize, Deserialize),
+  serde(bound(
+    serialize = "C::VertexIndex: Serialize, C::TextureIndex: Serialize, C::MaterialIndex: Serialize, C::BoneIndex: Serialize, C::MorphIndex: Serialize, C::RigidbodyIndex: Serialize, C::Vec2: Serialize, C::Vec3: Serialize, C::Vec4: Serialize, C::AdditionalVec4s: Serialize",
+    deserialize = "C::VertexIndex: Deserialize<'de>, C::TextureIndex: Deserialize<'de>, C::MaterialIndex: Deserialize<'de>, C::BoneIndex: Deserialize<'de>, C::MorphIndex: Deserialize<'de>, C::RigidbodyIndex: Deserialize<'de>, C::Vec2: Deserialize<'de>, C::Vec3: Deserialize<'de>, C::Vec4: Deserialize<'de>, C::AdditionalVec4s: Deserialize<'de>"
+  ))
+)]
+pub struct Model<C: Config> {
+  pub version: f32,
+  pub settings: Settings,
+  pub model_local_name: String,
+  pub model_universal_name: String,
+  pub local_comments: String,
+  pub universal_comments: String,
+  pub vertices: Vec<Vertex<C>>,
+  pub surfaces: Vec<[C::VertexIndex; 3]>,
+  pub textures: Vec<String>,
+  pub materials: Vec<Material<C>>,
+  pub bones: Vec<Bone<C>>,
+  pub morphs: Vec<Morph<C>>,
+  pub display_frames: Vec<DisplayFrame<C>>,
+  pub rigid_bodies: Vec<RigidBody<C>>,
+  pub joints: Vec<Joint<C>>,
+  pub soft_bodies: Vec<SoftBody<C>>,
+}
+
+impl<C: Config> Model<C> {
+  pub fn read<R: Read>(read: R) -> Result<Model<C>> {
+    Model::read_with_limits(read, Limits::default())
+  }
+
+  pub fn read_with_limits<R: Read>(read: R, limits: Limits) -> Result<Model<C>> {
+    let header = HeaderReader::with_limits(read, limits)?;
+    let version = header.version;
+    let settings = header.settings;
+    let model_local_name = header.model_local_name.clone();
+    let model_universal_name = header.model_universal_name.clone();
+    let local_comments = header.local_comments.clone();
+    let universal_comments = header.universal_comments.clone();
+
+    let mut vertices = VertexReader::new(header)?;
+    let vertex_list = vertices.iter::<C>().collect::<Result<_>>()?;
+
+    let mut surfaces = SurfaceReader::new(vertices)?;
+    let surface_list = surfaces.iter::<C>().collect::<Result<_>>()?;
+
+    let mut textures = TextureReader::new(surfaces)?;
+    let texture_list = textures.iter().collect::<Result<_>>()?;
+
+    let mut materials = MaterialReader::new(textures)?;
+    let material_list = materials.iter::<C>().collect::<Result<_>>()?;
+
+    let mut bones = BoneReader::new(materials)?;
+    let bone_list = bones.iter::<C>().collect::<Result<_>>()?;
+
+    let mut morphs = MorphReader::new(bones)?;
+    let morph_list = morphs.iter::<C>().collect::<Result<_>>()?;
+
+    let mut display_frames = DisplayReader::new(morphs)?;
+    let display_frame_list = display_frames.iter::<C>().collect::<Result<_>>()?;
+
+    let mut rigid_bodies = RigidBodyReader::new(display_frames)?;
+    let rigid_body_list = rigid_bodies.iter::<C>().collect::<Result<_>>()?;
+
+    let mut joints = JointReader::new(rigid_bodies)?;
+    let joint_list = joints.iter::<C>().collect::<Result<_>>()?;
+
+    let mut soft_bodies = SoftBodyReader::new(joints)?;
+    let soft_body_list = soft_bodies.iter::<C>().collect::<Result<_>>()?;
+
+    Ok(Model {
+      version,
+      settings,
+      model_local_name,
+      model_universal_name,
+      local_comments,
+      universal_comments,
+      vertices: vertex_list,
+      surfaces: surface_list,
+      textures: texture_list,
+      materials: material_list,
+      bones: bone_list,
+      morphs: morph_list,
+      display_frames: display_frame_list,
+      rigid_bodies: rigid_body_list,
+      joints: joint_list,
+      soft_bodies: soft_body_list,
+    })
+  }
+
+  pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Model<C>> {
+    Model::read(BufReader::new(File::open(path)?))
+  }
+
+  pub fn write<W: Write>(&self, write: W) -> Result<W> {
+    let mut header = HeaderWriter::new(write, self.settings);
+    header.version = self.version;
+    header.model_local_name = self.model_local_name.clone();
+    header.model_universal_name = self.model_universal_name.clone();
+    header.local_comments = self.local_comments.clone();
+    header.universal_comments = self.universal_comments.clone();
+
+    let mut vertices = VertexWriter::new(header, count(self.vertices.len())?)?;
+    for vertex in &self.vertices {
+      vertices.write(vertex)?;
+    }
+
+    let mut surfaces = SurfaceWriter::new(vertices, count(self.surfaces.len().saturating_mul(3))?)?;
+    for surface in &self.surfaces {
+      surfaces.write::<C>(surface)?;
+    }
+
+    let mut textures = TextureWriter::new(surfaces, count(self.textures.len())?)?;
+    for texture in &self.textures {
+      textures.write(texture)?;
+    }
+
+    let mut materials = MaterialWriter::new(textures, count(self.materials.len())?)?;
+    for material in &self.materials {
+      materials.write(material)?;
+    }
+
+    let mut bones = BoneWriter::new(materials, count(self.bones.len())?)?;
+    for bone in &self.bones {
+      bones.write(bone)?;
+    }
+
+    let mut morphs = MorphWriter::new(bones, count(self.morphs.len())?)?;
+    for morph in &self.morphs {
+      morphs.write(morph)?;
+    }
+
+    let mut display_frames = DisplayWriter::new(morphs, count(self.display_frames.len())?)?;
+    for display_frame in &self.display_frames {
+      display_frames.write(display_frame)?;
+    }
+
+    let mut rigid_bodies = RigidBodyWriter::new(display_frames, count(self.rigid_bodies.len())?)?;
+    for rigid_body in &self.rigid_bodies {
+      rigid_bodies.write(rigid_body)?;
+    }
+
+    let mut joints = JointWriter::new(rigid_bodies, count(self.joints.len())?)?;
+    for joint in &self.joints {
+      joints.write(joint)?;
+    }
+
+    let mut soft_bodies = SoftBodyWriter::new(joints, count(self.soft_bodies.len())?)?;
+    for soft_body in &self.soft_bodies {
+      soft_bodies.write(soft_body)?;
+    }
+
+    soft_bodies.finish()
+  }
+
+  pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+    self.write(BufWriter::new(File::create(path)?))?;
+    Ok(())
+  }
+
+  // the smallest index sizes that fit every element, keeping the encoding and the other globals
+  pub fn minimal_settings(&self) -> Settings {
+    Settings {
+      vertex_index_size: IndexSize::for_vertices(self.vertices.len()),
+      texture_index_size: IndexSize::for_elements(self.textures.len()),
+      material_index_size: IndexSize::for_elements(self.materials.len()),
+      bone_index_size: IndexSize::for_elements(self.bones.len()),
+      morph_index_size: IndexSize::for_elements(self.morphs.len()),
+      rigidbody_index_size: IndexSize::for_elements(self.rigid_bodies.len()),
+      ..self.settings
+    }
+  }
+
+  // texts and indices are held decoded, so new settings only change how the model is written
+  pub fn reencode(&mut self, settings: Settings) -> Result<()> {
+    if settings.additional_vec4_count != self.settings.additional_vec4_count {
+      return Err(Error::WrongAdditionalVec4Count(
+        settings.additional_vec4_count.into(),
+      ));
+    }
+
+    let minimal = self.minimal_settings();
+    let sizes = [
+      (
+        settings.vertex_index_size,
+        minimal.vertex_index_size,
+        self.vertices.len(),
+      ),
+      (
+        settings.texture_index_size,
+        minimal.texture_index_size,
+        self.textures.len(),
+      ),
+      (
+        settings.material_index_size,
+        minimal.material_index_size,
+        self.materials.len(),
+      ),
+      (
+        settings.bone_index_size,
+        minimal.bone_index_size,
+        self.bones.len(),
+      ),
+      (
+        settings.morph_index_size,
+        minimal.morph_index_size,
+        self.morphs.len(),
+      ),
+      (
+        settings.rigidbody_index_size,
+        minimal.rigidbody_index_size,
+        self.rigid_bodies.len(),
+      ),
+    ];
+    for &(size, needed, count) in &sizes {
+      if (size as u8) < (needed as u8) {
+        return Err(Error::IndexOverflow(count as i64 - 1));
+      }
+    }
+
+    self.settings = settings;
+    Ok(())
+  }
+}
+
+// section counts are written as i32, so a longer section cannot be stored
+fn count(len: usize) -> Result<i32> {
+  i32::try_from(len).map_err(|_| Error::IndexOverflow(len as i64))
+}
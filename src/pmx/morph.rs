@@ -1,9 +1,12 @@
 use crate::{Config, Error};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 use std::convert::TryFrom;
 use std::fmt::{Debug, Display, Formatter};
 
 #[derive(Copy, Clone, Debug, PartialEq)]
 #[repr(u8)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Panel {
   Hidden,
   Eyebrows,
@@ -40,6 +43,14 @@ impl From<u8> for Panel {
 }
 
 #[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(Serialize, Deserialize),
+  serde(bound(
+    serialize = "C::MorphIndex: Serialize",
+    deserialize = "C::MorphIndex: Deserialize<'de>"
+  ))
+)]
 pub struct GroupOffset<C: Config> {
   pub morph: C::MorphIndex,
   pub influence: f32,
@@ -55,6 +66,14 @@ where
 }
 
 #[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(Serialize, Deserialize),
+  serde(bound(
+    serialize = "C::VertexIndex: Serialize, C::Vec3: Serialize",
+    deserialize = "C::VertexIndex: Deserialize<'de>, C::Vec3: Deserialize<'de>"
+  ))
+)]
 pub struct VertexOffset<C: Config> {
   pub vertex: C::VertexIndex,
   pub offset: C::Vec3,
@@ -71,6 +90,14 @@ where
 }
 
 #[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(Serialize, Deserialize),
+  serde(bound(
+    serialize = "C::BoneIndex: Serialize, C::Vec3: Serialize, C::Vec4: Serialize",
+    deserialize = "C::BoneIndex: Deserialize<'de>, C::Vec3: Deserialize<'de>, C::Vec4: Deserialize<'de>"
+  ))
+)]
 pub struct BoneOffset<C: Config> {
   pub bone: C::BoneIndex,
   pub translation: C::Vec3,
@@ -93,6 +120,14 @@ where
 }
 
 #[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(Serialize, Deserialize),
+  serde(bound(
+    serialize = "C::VertexIndex: Serialize, C::Vec4: Serialize",
+    deserialize = "C::VertexIndex: Deserialize<'de>, C::Vec4: Deserialize<'de>"
+  ))
+)]
 pub struct UVOffset<C: Config> {
   pub vertex: C::VertexIndex,
   pub offset: C::Vec4,
@@ -110,6 +145,7 @@ where
 
 #[derive(Copy, Clone, Debug, PartialEq)]
 #[repr(u8)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum OffsetMethod {
   Multiply = 0,
   Additive = 1,
@@ -137,6 +173,14 @@ impl TryFrom<u8> for OffsetMethod {
 }
 
 #[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(Serialize, Deserialize),
+  serde(bound(
+    serialize = "C::MaterialIndex: Serialize, C::Vec3: Serialize, C::Vec4: Serialize",
+    deserialize = "C::MaterialIndex: Deserialize<'de>, C::Vec3: Deserialize<'de>, C::Vec4: Deserialize<'de>"
+  ))
+)]
 pub struct MaterialOffset<C: Config> {
   pub material: C::MaterialIndex,
   pub method: OffsetMethod,
@@ -175,6 +219,14 @@ texture tint: {}, environment tint: {}, toon tint: {}",
 }
 
 #[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(Serialize, Deserialize),
+  serde(bound(
+    serialize = "C::RigidbodyIndex: Serialize, C::Vec3: Serialize",
+    deserialize = "C::RigidbodyIndex: Deserialize<'de>, C::Vec3: Deserialize<'de>"
+  ))
+)]
 pub struct ImpulseOffset<C: Config> {
   pub rigid_body: C::RigidbodyIndex,
   pub local: bool,
@@ -200,6 +252,14 @@ where
 }
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(Serialize, Deserialize),
+  serde(bound(
+    serialize = "C::VertexIndex: Serialize, C::MaterialIndex: Serialize, C::BoneIndex: Serialize, C::MorphIndex: Serialize, C::RigidbodyIndex: Serialize, C::Vec3: Serialize, C::Vec4: Serialize",
+    deserialize = "C::VertexIndex: Deserialize<'de>, C::MaterialIndex: Deserialize<'de>, C::BoneIndex: Deserialize<'de>, C::MorphIndex: Deserialize<'de>, C::RigidbodyIndex: Deserialize<'de>, C::Vec3: Deserialize<'de>, C::Vec4: Deserialize<'de>"
+  ))
+)]
 pub enum Offsets<C: Config> {
   Group(Vec<GroupOffset<C>>),
   Vertex(Vec<VertexOffset<C>>),
@@ -232,6 +292,14 @@ impl<C: Config> Display for Offsets<C> {
   }
 }
 
+#[cfg_attr(
+  feature = "serde",
+  derive(Serialize, Deserialize),
+  serde(bound(
+    serialize = "C::VertexIndex: Serialize, C::MaterialIndex: Serialize, C::BoneIndex: Serialize, C::MorphIndex: Serialize, C::RigidbodyIndex: Serialize, C::Vec3: Serialize, C::Vec4: Serialize",
+    deserialize = "C::VertexIndex: Deserialize<'de>, C::MaterialIndex: Deserialize<'de>, C::BoneIndex: Deserialize<'de>, C::MorphIndex: Deserialize<'de>, C::RigidbodyIndex: Deserialize<'de>, C::Vec3: Deserialize<'de>, C::Vec4: Deserialize<'de>"
+  ))
+)]
 pub struct Morph<C: Config> {
   pub local_name: String,
   pub universal_name: String,
@@ -0,0 +1,293 @@
+use crate::math::{self, Vec3};
+use crate::pmx::morph::{MaterialOffset, Morph, OffsetMethod, Offsets, UVOffset};
+use crate::pmx::skeleton::Transform;
+use crate::pmx::types::{raw, resolve};
+use crate::{Config, Error, Material, Model, Result};
+use std::convert::TryInto;
+use std::iter::once;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct MaterialFactors {
+  pub diffuse_color: [f32; 4],
+  pub specular_color: [f32; 3],
+  pub specular_strength: f32,
+  pub ambient_color: [f32; 3],
+  pub edge_color: [f32; 4],
+  pub edge_scale: f32,
+  pub texture_tint: [f32; 4],
+  pub environment_tint: [f32; 4],
+  pub toon_tint: [f32; 4],
+}
+
+impl MaterialFactors {
+  pub fn splat(value: f32) -> Self {
+    MaterialFactors {
+      diffuse_color: [value; 4],
+      specular_color: [value; 3],
+      specular_strength: value,
+      ambient_color: [value; 3],
+      edge_color: [value; 4],
+      edge_scale: value,
+      texture_tint: [value; 4],
+      environment_tint: [value; 4],
+      toon_tint: [value; 4],
+    }
+  }
+
+  pub fn from_material<C: Config>(material: &Material<C>) -> Self {
+    MaterialFactors {
+      diffuse_color: math::vec4(&material.diffuse_color),
+      specular_color: math::vec3(&material.specular_color),
+      specular_strength: material.specular_strength,
+      ambient_color: math::vec3(&material.ambient_color),
+      edge_color: math::vec4(&material.edge_color),
+      edge_scale: material.edge_scale,
+      texture_tint: [1.0; 4],
+      environment_tint: [1.0; 4],
+      toon_tint: [1.0; 4],
+    }
+  }
+
+  pub fn from_offset<C: Config>(offset: &MaterialOffset<C>) -> Self {
+    MaterialFactors {
+      diffuse_color: math::vec4(&offset.diffuse_color),
+      specular_color: math::vec3(&offset.specular_color),
+      specular_strength: offset.specular_strength,
+      ambient_color: math::vec3(&offset.ambient_color),
+      edge_color: math::vec4(&offset.edge_color),
+      edge_scale: offset.edge_scale,
+      texture_tint: math::vec4(&offset.texture_tint),
+      environment_tint: math::vec4(&offset.environment_tint),
+      toon_tint: math::vec4(&offset.toon_tint),
+    }
+  }
+
+  fn each(&mut self, other: &MaterialFactors, mut f: impl FnMut(&mut f32, f32)) {
+    let pairs = self
+      .diffuse_color
+      .iter_mut()
+      .zip(&other.diffuse_color)
+      .chain(self.specular_color.iter_mut().zip(&other.specular_color))
+      .chain(once((
+        &mut self.specular_strength,
+        &other.specular_strength,
+      )))
+      .chain(self.ambient_color.iter_mut().zip(&other.ambient_color))
+      .chain(self.edge_color.iter_mut().zip(&other.edge_color))
+      .chain(once((&mut self.edge_scale, &other.edge_scale)))
+      .chain(self.texture_tint.iter_mut().zip(&other.texture_tint))
+      .chain(
+        self
+          .environment_tint
+          .iter_mut()
+          .zip(&other.environment_tint),
+      )
+      .chain(self.toon_tint.iter_mut().zip(&other.toon_tint));
+
+    for (a, b) in pairs {
+      f(a, *b);
+    }
+  }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct MaterialBlend {
+  pub multiply: MaterialFactors,
+  pub add: MaterialFactors,
+}
+
+impl Default for MaterialBlend {
+  fn default() -> Self {
+    MaterialBlend {
+      multiply: MaterialFactors::splat(1.0),
+      add: MaterialFactors::splat(0.0),
+    }
+  }
+}
+
+impl MaterialBlend {
+  pub fn apply<C: Config>(&self, material: &Material<C>) -> MaterialFactors {
+    let mut result = MaterialFactors::from_material(material);
+    result.each(&self.multiply, |a, b| *a *= b);
+    result.each(&self.add, |a, b| *a += b);
+    result
+  }
+
+  fn blend(&mut self, offset: &MaterialFactors, method: OffsetMethod, weight: f32) {
+    match method {
+      OffsetMethod::Multiply => self
+        .multiply
+        .each(offset, |a, b| *a *= 1.0 + (b - 1.0) * weight),
+      OffsetMethod::Additive => self.add.each(offset, |a, b| *a += b * weight),
+    }
+  }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Impulse {
+  pub rigid_body: usize,
+  pub local: bool,
+  pub velocity: Vec3,
+  pub torque: Vec3,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Morphed {
+  pub positions: Vec<Vec3>,
+  pub uvs: Vec<[f32; 4]>,
+  pub additional_uvs: Vec<Vec<[f32; 4]>>,
+  pub bones: Vec<Transform>,
+  pub materials: Vec<MaterialBlend>,
+  pub impulses: Vec<Impulse>,
+}
+
+// `weights` holds one weight per morph, missing ones are treated as zero
+pub fn evaluate<C: Config>(model: &Model<C>, weights: &[f32]) -> Result<Morphed> {
+  let vertices = model.vertices.len();
+  let mut morphing = Morphing {
+    model,
+    active: vec![false; model.morphs.len()],
+    result: Morphed {
+      positions: vec![[0.0; 3]; vertices],
+      uvs: vec![[0.0; 4]; vertices],
+      additional_uvs: vec![vec![[0.0; 4]; vertices]; model.settings.additional_vec4_count.into()],
+      bones: vec![Transform::default(); model.bones.len()],
+      materials: vec![MaterialBlend::default(); model.materials.len()],
+      impulses: Vec::new(),
+    },
+  };
+
+  for (morph, &weight) in weights.iter().enumerate().take(model.morphs.len()) {
+    morphing.apply(morph, weight)?;
+  }
+
+  Ok(morphing.result)
+}
+
+struct Morphing<'a, C: Config> {
+  model: &'a Model<C>,
+  active: Vec<bool>,
+  result: Morphed,
+}
+
+impl<'a, C: Config> Morphing<'a, C> {
+  fn apply(&mut self, morph: usize, weight: f32) -> Result<()> {
+    if weight == 0.0 {
+      return Ok(());
+    }
+
+    if self.active[morph] {
+      return Err(Error::MorphCycle(morph));
+    }
+    self.active[morph] = true;
+
+    let model = self.model;
+    let result = &mut self.result;
+    let Morph { offsets, .. } = &model.morphs[morph];
+
+    match offsets {
+      Offsets::Group(offsets) => {
+        for offset in offsets {
+          let child = index(&offset.morph, model.morphs.len(), Error::InvalidMorphIndex)?;
+          self.apply(child, weight * offset.influence)?;
+        }
+      }
+      Offsets::Flip(offsets) => {
+        let selected = ((offsets.len() + 1) as f32 * weight) as usize;
+        if let Some(offset) = offsets.get(selected.min(offsets.len()).wrapping_sub(1)) {
+          let child = index(&offset.morph, model.morphs.len(), Error::InvalidMorphIndex)?;
+          self.apply(child, offset.influence)?;
+        }
+      }
+      Offsets::Vertex(offsets) => {
+        for offset in offsets {
+          let vertex = index(
+            &offset.vertex,
+            result.positions.len(),
+            Error::InvalidVertexIndex,
+          )?;
+          let delta = math::scale(math::vec3(&offset.offset), weight);
+          result.positions[vertex] = math::add(result.positions[vertex], delta);
+        }
+      }
+      Offsets::UV(offsets) => uv(&mut result.uvs, offsets, weight)?,
+      Offsets::AdditionalUV1(offsets) => additional_uv(result, 0, offsets, weight)?,
+      Offsets::AdditionalUV2(offsets) => additional_uv(result, 1, offsets, weight)?,
+      Offsets::AdditionalUV3(offsets) => additional_uv(result, 2, offsets, weight)?,
+      Offsets::AdditionalUV4(offsets) => additional_uv(result, 3, offsets, weight)?,
+      Offsets::Bone(offsets) => {
+        for offset in offsets {
+          let bone = index(&offset.bone, result.bones.len(), Error::InvalidBoneIndex)?;
+          let transform = &mut result.bones[bone];
+          let translation = math::scale(math::vec3(&offset.translation), weight);
+          let rotation =
+            math::quat_slerp(math::QUAT_IDENTITY, math::vec4(&offset.rotation), weight);
+          transform.translation = math::add(transform.translation, translation);
+          transform.rotation = math::quat_mul(transform.rotation, rotation);
+        }
+      }
+      Offsets::Material(offsets) => {
+        for offset in offsets {
+          let factors = MaterialFactors::from_offset(offset);
+          let material = raw(&offset.material);
+          if material == -1 {
+            for blend in &mut result.materials {
+              blend.blend(&factors, offset.method, weight);
+            }
+          } else {
+            let material = index(
+              &material,
+              result.materials.len(),
+              Error::InvalidMaterialIndex,
+            )?;
+            result.materials[material].blend(&factors, offset.method, weight);
+          }
+        }
+      }
+      Offsets::Impulse(offsets) => {
+        for offset in offsets {
+          let rigid_body = index(
+            &offset.rigid_body,
+            model.rigid_bodies.len(),
+            Error::InvalidRigidBodyIndex,
+          )?;
+          result.impulses.push(Impulse {
+            rigid_body,
+            local: offset.local,
+            velocity: math::scale(math::vec3(&offset.velocity), weight),
+            torque: math::scale(math::vec3(&offset.torque), weight),
+          });
+        }
+      }
+    }
+
+    self.active[morph] = false;
+    Ok(())
+  }
+}
+
+fn index<I: TryInto<i64> + Clone>(index: &I, len: usize, error: fn(i64) -> Error) -> Result<usize> {
+  resolve(index, len).ok_or_else(|| error(raw(index)))
+}
+
+fn uv<C: Config>(uvs: &mut [[f32; 4]], offsets: &[UVOffset<C>], weight: f32) -> Result<()> {
+  for offset in offsets {
+    let vertex = index(&offset.vertex, uvs.len(), Error::InvalidVertexIndex)?;
+    for (uv, delta) in uvs[vertex].iter_mut().zip(offset.offset.as_ref()) {
+      *uv += delta * weight;
+    }
+  }
+  Ok(())
+}
+
+fn additional_uv<C: Config>(
+  result: &mut Morphed,
+  slot: usize,
+  offsets: &[UVOffset<C>],
+  weight: f32,
+) -> Result<()> {
+  match result.additional_uvs.get_mut(slot) {
+    Some(uvs) => uv(uvs, offsets, weight),
+    None => Err(Error::WrongAdditionalVec4Count(slot + 1)),
+  }
+}
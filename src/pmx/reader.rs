@@ -1,22 +1,36 @@
+#[cfg(feature = "async")]
+pub mod asynchronous;
 pub mod bone;
 pub mod display;
 pub mod header;
-mod helpers;
+pub(crate) mod helpers;
+pub mod index;
 pub mod joint;
+pub mod limits;
 pub mod material;
 pub mod morph;
 pub mod rigid_body;
+pub mod soft_body;
 pub mod surface;
 pub mod texture;
 pub mod vertex;
 
+#[cfg(feature = "async")]
+pub use asynchronous::{
+  AsyncBoneReader, AsyncDisplayReader, AsyncHeaderReader, AsyncJointReader, AsyncMaterialReader,
+  AsyncMorphReader, AsyncRigidBodyReader, AsyncSoftBodyReader, AsyncSurfaceReader,
+  AsyncTextureReader, AsyncVertexReader,
+};
 pub use bone::BoneReader;
 pub use display::DisplayReader;
 pub use header::HeaderReader;
+pub use index::SectionIndex;
 pub use joint::JointReader;
+pub use limits::Limits;
 pub use material::MaterialReader;
 pub use morph::MorphReader;
 pub use rigid_body::RigidBodyReader;
+pub use soft_body::SoftBodyReader;
 pub use surface::SurfaceReader;
 pub use texture::TextureReader;
 pub use vertex::VertexReader;
@@ -0,0 +1,374 @@
+use crate::{
+  pmx::{
+    bone::Bone, display::DisplayFrame, error::Section, joint::Joint, morph::Morph,
+    rigid_body::RigidBody, soft_body::SoftBody,
+  },
+  reader::{helpers::Source, *},
+  Config, DefaultConfig, Error, Material, Model, Result, Settings, Vertex,
+};
+use futures_io::AsyncRead;
+use std::future::poll_fn;
+use std::io::ErrorKind;
+use std::pin::Pin;
+
+const CHUNK: usize = 8192;
+
+// the sync readers decode from whatever is buffered, and more is only read when an element was cut off
+struct Stream<R> {
+  read: R,
+  buf: Vec<u8>,
+  start: usize,
+  offset: u64,
+  eof: bool,
+  poison: bool,
+  limits: Limits,
+}
+
+impl<R: AsyncRead + Unpin> Stream<R> {
+  async fn decode<T>(
+    &mut self,
+    section: Section,
+    mut decode: impl FnMut(Source<&[u8]>) -> Result<(T, u64)>,
+  ) -> Result<T> {
+    if self.poison {
+      return Err(Error::Poisoned);
+    }
+
+    loop {
+      let source = Source::new(&self.buf[self.start..], self.limits).resume(section, self.offset);
+      let error = match decode(source) {
+        Ok((value, offset)) => {
+          self.start += (offset - self.offset) as usize;
+          self.offset = offset;
+          return Ok(value);
+        }
+        Err(e) => e,
+      };
+
+      let incomplete = matches!(error.kind(), Error::Io(e) if e.kind() == ErrorKind::UnexpectedEof);
+      if !incomplete || self.eof {
+        self.poison = true;
+        return Err(error);
+      }
+
+      if let Err(e) = self.fill().await {
+        self.poison = true;
+        return Err(e);
+      }
+    }
+  }
+
+  async fn count(&mut self, section: Section) -> Result<i32> {
+    self
+      .decode(section, |mut read| {
+        let count = read.read_count(section)?;
+        Ok((count, read.offset()))
+      })
+      .await
+  }
+
+  async fn fill(&mut self) -> Result<()> {
+    if self.start > self.buf.len() / 2 {
+      self.buf.drain(..self.start);
+      self.start = 0;
+    }
+
+    // the new space is filled completely before decoding again, so the buffer at least doubles and
+    // an element spanning many reads is only decoded a logarithmic number of times
+    let mut filled = self.buf.len();
+    self
+      .buf
+      .resize(filled + (filled - self.start).max(CHUNK), 0);
+
+    let result = loop {
+      if filled == self.buf.len() {
+        break Ok(());
+      }
+      let read = &mut self.read;
+      let buf = &mut self.buf[filled..];
+      match poll_fn(|cx| Pin::new(&mut *read).poll_read(cx, buf)).await {
+        Ok(0) => {
+          self.eof = true;
+          break Ok(());
+        }
+        Ok(size) => filled += size,
+        Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+        Err(e) => break Err(e),
+      }
+    };
+
+    self.buf.truncate(filled);
+    result.map_err(Error::from)
+  }
+}
+
+pub struct AsyncHeaderReader<R> {
+  pub version: f32,
+  pub settings: Settings,
+  pub model_local_name: String,
+  pub model_universal_name: String,
+  pub local_comments: String,
+  pub universal_comments: String,
+  stream: Stream<R>,
+}
+
+impl<R: AsyncRead + Unpin> AsyncHeaderReader<R> {
+  pub async fn new(read: R) -> Result<AsyncHeaderReader<R>> {
+    AsyncHeaderReader::with_limits(read, Limits::default()).await
+  }
+
+  pub async fn with_limits(read: R, limits: Limits) -> Result<AsyncHeaderReader<R>> {
+    let mut stream = Stream {
+      read,
+      buf: Vec::new(),
+      start: 0,
+      offset: 0,
+      eof: false,
+      poison: false,
+      limits,
+    };
+    let header = stream
+      .decode(Section::Header, |read| {
+        let header = HeaderReader::from_source(read)?;
+        let offset = header.read.offset();
+        let header = (
+          header.version,
+          header.settings,
+          header.model_local_name,
+          header.model_universal_name,
+          header.local_comments,
+          header.universal_comments,
+        );
+        Ok((header, offset))
+      })
+      .await?;
+    let (
+      version,
+      settings,
+      model_local_name,
+      model_universal_name,
+      local_comments,
+      universal_comments,
+    ) = header;
+
+    Ok(AsyncHeaderReader {
+      version,
+      settings,
+      model_local_name,
+      model_universal_name,
+      local_comments,
+      universal_comments,
+      stream,
+    })
+  }
+}
+
+// each reader finishes the previous section, reads the count of its own and then decodes every
+// element with the matching sync reader over the buffered bytes
+macro_rules! async_reader {
+  (
+    $name:ident($previous:ident), $section:ident, $reader:ident,
+    next$(<$c:ident>)? -> $item:ty $(, if $present:expr)?
+  ) => {
+    pub struct $name<R> {
+      pub version: f32,
+      pub settings: Settings,
+      pub count: i32,
+      pub remaining: i32,
+      stream: Stream<R>,
+    }
+
+    impl<R: AsyncRead + Unpin> $name<R> {
+      pub async fn new(mut previous: $previous<R>) -> Result<$name<R>> {
+        previous.skip().await?;
+        let present = true $(&& ($present)(previous.version))?;
+        let count = if present {
+          previous.stream.count(Section::$section).await?
+        } else {
+          0
+        };
+
+        Ok($name {
+          version: previous.version,
+          settings: previous.settings,
+          count,
+          remaining: count,
+          stream: previous.stream,
+        })
+      }
+
+      pub async fn next$(<$c: Config>)?(&mut self) -> Result<Option<$item>> {
+        let (version, settings, count, remaining) =
+          (self.version, self.settings, self.count, self.remaining);
+        let (value, remaining) = self
+          .stream
+          .decode(Section::$section, |read| {
+            let mut reader = $reader {
+              version,
+              settings,
+              count,
+              remaining,
+              read,
+              poison: false,
+            };
+            let value = reader.next$(::<$c>)?()?;
+            Ok(((value, reader.remaining), reader.read.offset()))
+          })
+          .await?;
+
+        self.remaining = remaining;
+        Ok(value)
+      }
+
+      // nothing follows soft bodies, so their reader never skips
+      #[allow(dead_code)]
+      async fn skip(&mut self) -> Result<()> {
+        $(type $c = DefaultConfig;)?
+        while self.next$(::<$c>)?().await?.is_some() {}
+        Ok(())
+      }
+    }
+  };
+}
+
+impl<R> AsyncHeaderReader<R> {
+  // the header is read whole by `new`, so there is nothing left of it to skip
+  async fn skip(&mut self) -> Result<()> {
+    Ok(())
+  }
+}
+
+async_reader!(AsyncVertexReader(AsyncHeaderReader), Vertices, VertexReader, next<C> -> Vertex<C>);
+async_reader!(
+  AsyncSurfaceReader(AsyncVertexReader),
+  Surfaces,
+  SurfaceReader,
+  next<C> -> [C::VertexIndex; 3]
+);
+async_reader!(AsyncTextureReader(AsyncSurfaceReader), Textures, TextureReader, next -> String);
+async_reader!(
+  AsyncMaterialReader(AsyncTextureReader),
+  Materials,
+  MaterialReader,
+  next<C> -> Material<C>
+);
+async_reader!(AsyncBoneReader(AsyncMaterialReader), Bones, BoneReader, next<C> -> Bone<C>);
+async_reader!(AsyncMorphReader(AsyncBoneReader), Morphs, MorphReader, next<C> -> Morph<C>);
+async_reader!(
+  AsyncDisplayReader(AsyncMorphReader),
+  DisplayFrames,
+  DisplayReader,
+  next<C> -> DisplayFrame<C>
+);
+async_reader!(
+  AsyncRigidBodyReader(AsyncDisplayReader),
+  RigidBodies,
+  RigidBodyReader,
+  next<C> -> RigidBody<C>
+);
+async_reader!(AsyncJointReader(AsyncRigidBodyReader), Joints, JointReader, next<C> -> Joint<C>);
+async_reader!(
+  AsyncSoftBodyReader(AsyncJointReader),
+  SoftBodies,
+  SoftBodyReader,
+  next<C> -> SoftBody<C>,
+  if |version| version >= 2.1
+);
+
+impl<C: Config> Model<C> {
+  pub async fn read_async<R: AsyncRead + Unpin>(read: R) -> Result<Model<C>> {
+    Model::read_async_with_limits(read, Limits::default()).await
+  }
+
+  pub async fn read_async_with_limits<R: AsyncRead + Unpin>(
+    read: R,
+    limits: Limits,
+  ) -> Result<Model<C>> {
+    let header = AsyncHeaderReader::with_limits(read, limits).await?;
+    let version = header.version;
+    let settings = header.settings;
+    let model_local_name = header.model_local_name.clone();
+    let model_universal_name = header.model_universal_name.clone();
+    let local_comments = header.local_comments.clone();
+    let universal_comments = header.universal_comments.clone();
+
+    let mut reader = AsyncVertexReader::new(header).await?;
+    let mut vertices = Vec::new();
+    while let Some(vertex) = reader.next::<C>().await? {
+      vertices.push(vertex);
+    }
+
+    let mut reader = AsyncSurfaceReader::new(reader).await?;
+    let mut surfaces = Vec::new();
+    while let Some(surface) = reader.next::<C>().await? {
+      surfaces.push(surface);
+    }
+
+    let mut reader = AsyncTextureReader::new(reader).await?;
+    let mut textures = Vec::new();
+    while let Some(texture) = reader.next().await? {
+      textures.push(texture);
+    }
+
+    let mut reader = AsyncMaterialReader::new(reader).await?;
+    let mut materials = Vec::new();
+    while let Some(material) = reader.next::<C>().await? {
+      materials.push(material);
+    }
+
+    let mut reader = AsyncBoneReader::new(reader).await?;
+    let mut bones = Vec::new();
+    while let Some(bone) = reader.next::<C>().await? {
+      bones.push(bone);
+    }
+
+    let mut reader = AsyncMorphReader::new(reader).await?;
+    let mut morphs = Vec::new();
+    while let Some(morph) = reader.next::<C>().await? {
+      morphs.push(morph);
+    }
+
+    let mut reader = AsyncDisplayReader::new(reader).await?;
+    let mut display_frames = Vec::new();
+    while let Some(display_frame) = reader.next::<C>().await? {
+      display_frames.push(display_frame);
+    }
+
+    let mut reader = AsyncRigidBodyReader::new(reader).await?;
+    let mut rigid_bodies = Vec::new();
+    while let Some(rigid_body) = reader.next::<C>().await? {
+      rigid_bodies.push(rigid_body);
+    }
+
+    let mut reader = AsyncJointReader::new(reader).await?;
+    let mut joints = Vec::new();
+    while let Some(joint) = reader.next::<C>().await? {
+      joints.push(joint);
+    }
+
+    let mut reader = AsyncSoftBodyReader::new(reader).await?;
+    let mut soft_bodies = Vec::new();
+    while let Some(soft_body) = reader.next::<C>().await? {
+      soft_bodies.push(soft_body);
+    }
+
+    Ok(Model {
+      version,
+      settings,
+      model_local_name,
+      model_universal_name,
+      local_comments,
+      universal_comments,
+      vertices,
+      surfaces,
+      textures,
+      materials,
+      bones,
+      morphs,
+      display_frames,
+      rigid_bodies,
+      joints,
+      soft_bodies,
+    })
+  }
+}
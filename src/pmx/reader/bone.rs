@@ -1,30 +1,38 @@
 use crate::{
   pmx::bone::*,
-  reader::{helpers::ReadHelpers, MaterialReader},
+  pmx::error::Section,
+  reader::{
+    helpers::{with_capacity, ReadHelpers, Source},
+    MaterialReader,
+  },
   Config, DefaultConfig, Error, Result, Settings,
 };
 use byteorder::{ReadBytesExt, LE};
 use enumflags2::BitFlags;
+use std::io::Read;
 use std::marker::PhantomData;
-use std::{io::Read, usize};
 
 pub struct BoneReader<R> {
+  pub version: f32,
   pub settings: Settings,
   pub count: i32,
   pub remaining: i32,
-  pub(crate) read: R,
+  pub(crate) read: Source<R>,
   pub(crate) poison: bool,
 }
 
 impl<R: Read> BoneReader<R> {
   pub fn new(mut m: MaterialReader<R>) -> Result<BoneReader<R>> {
-    assert!(!m.poison);
+    if m.poison {
+      return Err(Error::Poisoned);
+    }
     while m.remaining > 0 {
       m.next::<DefaultConfig>()?;
     }
-    let count = m.read.read_i32::<LE>()?;
+    let count = m.read.read_count(Section::Bones)?;
 
     Ok(BoneReader {
+      version: m.version,
       settings: m.settings,
       count,
       remaining: count,
@@ -34,12 +42,14 @@ impl<R: Read> BoneReader<R> {
   }
 
   pub fn next<C: Config>(&mut self) -> Result<Option<Bone<C>>> {
-    assert!(!self.poison);
+    if self.poison {
+      return Err(Error::Poisoned);
+    }
     let result = self.next_impl::<C>();
     if result.is_err() {
       self.poison = true;
     }
-    result
+    result.map_err(|e| self.read.locate(e))
   }
 
   fn next_impl<C: Config>(&mut self) -> Result<Option<Bone<C>>> {
@@ -47,23 +57,24 @@ impl<R: Read> BoneReader<R> {
       return Ok(None);
     }
 
+    self.read.element(self.count - self.remaining);
     self.remaining -= 1;
 
     let local_name = self.read.read_text(self.settings.text_encoding)?;
+
+    self.read.name(&local_name);
     let universal_name = self.read.read_text(self.settings.text_encoding)?;
     let position = self.read.read_vec3::<C>()?;
     let parent = self.read.read_index(self.settings.bone_index_size)?;
     let transform_level = self.read.read_i32::<LE>()?;
-    let bone_flags = BitFlags::from_bits(self.read.read_u16::<LE>()?).unwrap();
+    let bits = self.read.read_u16::<LE>()?;
+    let bone_flags = BitFlags::from_bits(bits).map_err(|_| Error::UnknownBoneFlags(bits))?;
 
-    let connection = bone_flags
-      .contains(BoneFlags::Connection)
-      .then(|| {
-        Ok::<_, Error>(Connection::Index(
-          self.read.read_index(self.settings.bone_index_size)?,
-        ))
-      })
-      .unwrap_or_else(|| Ok::<_, Error>(Connection::Position(self.read.read_vec3::<C>()?)))?;
+    let connection = if bone_flags.contains(BoneFlags::Connection) {
+      Connection::Index(self.read.read_index(self.settings.bone_index_size)?)
+    } else {
+      Connection::Position(self.read.read_vec3::<C>()?)
+    };
 
     let additional = bone_flags
       .intersects(BoneFlags::AddRotation | BoneFlags::AddMovement)
@@ -99,9 +110,10 @@ impl<R: Read> BoneReader<R> {
       let ik_bone = self.read.read_index(self.settings.bone_index_size)?;
       let iterations = self.read.read_u32::<LE>()?;
       let limit_angle = self.read.read_f32::<LE>()?;
-      let link_count = self.read.read_u32::<LE>()? as usize;
-      let mut links = Vec::with_capacity(link_count);
-      for _i in 0..link_count {
+      let link_count = self.read.read_item_count()?;
+      let mut links = with_capacity(link_count);
+      for i in 0..link_count {
+        self.read.item(i);
         let ik_bone = self
           .read
           .read_index::<C::BoneIndex>(self.settings.bone_index_size)?;
@@ -139,7 +151,7 @@ impl<R: Read> BoneReader<R> {
     }))
   }
 
-  pub fn iter<C>(&mut self) -> BoneIterator<R, C> {
+  pub fn iter<C>(&mut self) -> BoneIterator<'_, R, C> {
     BoneIterator {
       reader: self,
       phantom: PhantomData,
@@ -1,29 +1,37 @@
 use crate::{
   pmx::display::*,
-  reader::{helpers::ReadHelpers, MorphReader},
+  pmx::error::Section,
+  reader::{
+    helpers::{with_capacity, ReadHelpers, Source},
+    MorphReader,
+  },
   Config, DefaultConfig, Error, Result, Settings,
 };
-use byteorder::{ReadBytesExt, LE};
+use byteorder::ReadBytesExt;
 use std::io::Read;
 use std::marker::PhantomData;
 
 pub struct DisplayReader<R> {
+  pub version: f32,
   pub settings: Settings,
   pub count: i32,
   pub remaining: i32,
-  pub(crate) read: R,
+  pub(crate) read: Source<R>,
   pub(crate) poison: bool,
 }
 
 impl<R: Read> DisplayReader<R> {
   pub fn new(mut m: MorphReader<R>) -> Result<DisplayReader<R>> {
-    assert!(!m.poison);
+    if m.poison {
+      return Err(Error::Poisoned);
+    }
     while m.remaining > 0 {
       m.next::<DefaultConfig>()?;
     }
-    let count = m.read.read_i32::<LE>()?;
+    let count = m.read.read_count(Section::DisplayFrames)?;
 
     Ok(DisplayReader {
+      version: m.version,
       settings: m.settings,
       count,
       remaining: count,
@@ -33,12 +41,14 @@ impl<R: Read> DisplayReader<R> {
   }
 
   pub fn next<C: Config>(&mut self) -> Result<Option<DisplayFrame<C>>> {
-    assert!(!self.poison);
+    if self.poison {
+      return Err(Error::Poisoned);
+    }
     let result = self.next_impl::<C>();
     if result.is_err() {
       self.poison = true;
     }
-    result
+    result.map_err(|e| self.read.locate(e))
   }
 
   fn next_impl<C: Config>(&mut self) -> Result<Option<DisplayFrame<C>>> {
@@ -46,15 +56,19 @@ impl<R: Read> DisplayReader<R> {
       return Ok(None);
     }
 
+    self.read.element(self.count - self.remaining);
     self.remaining -= 1;
 
     let local_name = self.read.read_text(self.settings.text_encoding)?;
+
+    self.read.name(&local_name);
     let universal_name = self.read.read_text(self.settings.text_encoding)?;
     let special_flag = self.read.read_u8()? != 0;
-    let frame_count = self.read.read_u32::<LE>()?;
-    let mut frames = Vec::with_capacity(frame_count as usize);
+    let frame_count = self.read.read_item_count()?;
+    let mut frames = with_capacity(frame_count);
 
-    for _ in 0..frame_count {
+    for i in 0..frame_count {
+      self.read.item(i);
       let frame = match self.read.read_u8()? {
         0 => Frame::Bone(self.read.read_index(self.settings.bone_index_size)?),
         1 => Frame::Morph(self.read.read_index(self.settings.morph_index_size)?),
@@ -72,7 +86,7 @@ impl<R: Read> DisplayReader<R> {
     }))
   }
 
-  pub fn iter<C>(&mut self) -> DisplayIterator<R, C> {
+  pub fn iter<C>(&mut self) -> DisplayIterator<'_, R, C> {
     DisplayIterator {
       reader: self,
       phantom: PhantomData,
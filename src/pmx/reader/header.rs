@@ -1,6 +1,5 @@
-use crate::{pmx::types::*, reader::helpers::ReadHelpers, Error, Settings};
+use crate::{reader::helpers::Source, reader::Limits, Error, Settings};
 use byteorder::{ReadBytesExt, LE};
-use std::convert::TryFrom;
 use std::fmt::{Display, Formatter};
 use std::io::Read;
 
@@ -11,50 +10,64 @@ pub struct HeaderReader<R> {
   pub model_universal_name: String,
   pub local_comments: String,
   pub universal_comments: String,
-  pub(crate) read: R,
+  pub(crate) read: Source<R>,
 }
 
 impl<R: Read> HeaderReader<R> {
-  pub fn new(mut read: R) -> Result<HeaderReader<R>, Error> {
-    let mut magic = [0u8; 4];
-    read.read_exact(&mut magic)?;
-    if magic != [0x50, 0x4D, 0x58, 0x20] {
-      return Err(Error::WrongSignature(magic));
-    }
-
-    let version = read.read_f32::<LE>()?;
-    let globals_count = read.read_u8()?;
-    if globals_count < 8 {
-      return Err(Error::GlobalsCountLessThan8(globals_count));
-    }
+  pub fn new(read: R) -> Result<HeaderReader<R>, Error> {
+    HeaderReader::with_limits(read, Limits::default())
+  }
 
-    let mut globals = Vec::with_capacity(globals_count as usize);
-    globals.resize(globals_count as usize, 0u8);
-    read.read_exact(&mut globals)?;
+  pub fn with_limits(read: R, limits: Limits) -> Result<HeaderReader<R>, Error> {
+    HeaderReader::from_source(Source::new(read, limits))
+  }
 
-    let settings = Settings {
-      text_encoding: TextEncoding::try_from(globals[0])?,
-      additional_vec4_count: globals[1],
-      vertex_index_size: IndexSize::try_from(globals[2])?,
-      texture_index_size: IndexSize::try_from(globals[3])?,
-      material_index_size: IndexSize::try_from(globals[4])?,
-      bone_index_size: IndexSize::try_from(globals[5])?,
-      morph_index_size: IndexSize::try_from(globals[6])?,
-      rigidbody_index_size: IndexSize::try_from(globals[7])?,
-    };
+  pub(crate) fn from_source(mut read: Source<R>) -> Result<HeaderReader<R>, Error> {
+    let (
+      version,
+      settings,
+      [model_local_name, model_universal_name, local_comments, universal_comments],
+    ) = read_header(&mut read).map_err(|e| read.locate(e))?;
 
-    Ok(HeaderReader::<R> {
+    Ok(HeaderReader {
       version,
       settings,
-      model_local_name: read.read_text(settings.text_encoding)?,
-      model_universal_name: read.read_text(settings.text_encoding)?,
-      local_comments: read.read_text(settings.text_encoding)?,
-      universal_comments: read.read_text(settings.text_encoding)?,
+      model_local_name,
+      model_universal_name,
+      local_comments,
+      universal_comments,
       read,
     })
   }
 }
 
+fn read_header<R: Read>(read: &mut Source<R>) -> Result<(f32, Settings, [String; 4]), Error> {
+  let mut magic = [0u8; 4];
+  read.read_exact(&mut magic)?;
+  if magic != [0x50, 0x4D, 0x58, 0x20] {
+    return Err(Error::WrongSignature(magic));
+  }
+
+  let version = read.read_f32::<LE>()?;
+  let globals_count = read.read_u8()?;
+  if globals_count < 8 {
+    return Err(Error::GlobalsCountLessThan8(globals_count));
+  }
+
+  let mut globals = vec![0u8; globals_count as usize];
+  read.read_exact(&mut globals)?;
+  let settings = Settings::from_globals(&globals)?;
+
+  let texts = [
+    read.read_text(settings.text_encoding)?,
+    read.read_text(settings.text_encoding)?,
+    read.read_text(settings.text_encoding)?,
+    read.read_text(settings.text_encoding)?,
+  ];
+
+  Ok((version, settings, texts))
+}
+
 impl<R> Display for HeaderReader<R> {
   fn fmt(&self, f: &mut Formatter) -> Result<(), std::fmt::Error> {
     write!(f, "version: {}, ", self.version)?;
@@ -1,22 +1,13 @@
+use crate::pmx::error::{Location, Section};
+use crate::reader::Limits;
 use crate::{pmx::types::*, Error, Result};
 use byteorder::{ReadBytesExt, LE};
 use encoding::all::{UTF_16LE, UTF_8};
 use encoding::{DecoderTrap, Encoding};
-use std::io::Read;
+use std::convert::TryFrom;
+use std::io::{Read, Seek, SeekFrom};
 
 pub(crate) trait ReadHelpers: Read {
-  fn read_text(&mut self, encoding: TextEncoding) -> Result<String> {
-    let size = self.read_i32::<LE>()?;
-    let mut buf = Vec::with_capacity(size as usize);
-    buf.resize(size as usize, 0u8);
-    self.read_exact(&mut buf)?;
-    (match encoding {
-      TextEncoding::UTF8 => UTF_8.decode(&buf, DecoderTrap::Strict),
-      TextEncoding::UTF16LE => UTF_16LE.decode(&buf, DecoderTrap::Strict),
-    })
-    .map_err(|e| Error::DecodeText(e))
-  }
-
   fn read_vec2<C: Config>(&mut self) -> Result<C::Vec2> {
     Ok([self.read_f32::<LE>()?, self.read_f32::<LE>()?].into())
   }
@@ -80,3 +71,158 @@ pub(crate) trait ReadHelpers: Read {
 }
 
 impl<R: Read> ReadHelpers for R {}
+
+// declared lengths are only trusted up to this many elements, the rest grows with the data actually read
+const PREALLOCATED: usize = 1024;
+
+pub(crate) fn with_capacity<T>(count: usize) -> Vec<T> {
+  Vec::with_capacity(count.min(PREALLOCATED))
+}
+
+// counts consumed bytes and remembers what is being decoded, so errors can tell where they happened
+pub(crate) struct Source<R> {
+  read: R,
+  location: Location,
+  pub(crate) limits: Limits,
+}
+
+impl<R> Source<R> {
+  pub(crate) fn new(read: R, limits: Limits) -> Source<R> {
+    Source {
+      read,
+      limits,
+      location: Location {
+        offset: 0,
+        section: Section::Header,
+        element: None,
+        name: None,
+        item: None,
+      },
+    }
+  }
+
+  // continues a location from an earlier source, when decoding is resumed on a new buffer
+  #[cfg(feature = "async")]
+  pub(crate) fn resume(mut self, section: Section, offset: u64) -> Source<R> {
+    self.section(section);
+    self.location.offset = offset;
+    self
+  }
+
+  pub(crate) fn offset(&self) -> u64 {
+    self.location.offset
+  }
+
+  pub(crate) fn section(&mut self, section: Section) {
+    self.location.section = section;
+    self.location.element = None;
+    self.location.name = None;
+    self.location.item = None;
+  }
+
+  pub(crate) fn element(&mut self, element: i32) {
+    self.location.element = Some(element as usize);
+    self.location.name = None;
+    self.location.item = None;
+  }
+
+  pub(crate) fn name(&mut self, name: &str) {
+    self.location.name = Some(name.to_owned());
+  }
+
+  pub(crate) fn item(&mut self, item: usize) {
+    self.location.item = Some(item);
+  }
+
+  pub(crate) fn locate(&self, error: Error) -> Error {
+    match error {
+      Error::Located(..) => error,
+      error => Error::Located(Box::new(self.location.clone()), Box::new(error)),
+    }
+  }
+}
+
+impl<R: Read> Source<R> {
+  pub(crate) fn read_count(&mut self, section: Section) -> Result<i32> {
+    self.section(section);
+    let count = self
+      .read_i32::<LE>()
+      .map_err(Error::from)
+      .and_then(|count| length(count, self.limits.section_length))
+      .map_err(|e| self.locate(e))?;
+    Ok(count as i32)
+  }
+
+  pub(crate) fn read_item_count(&mut self) -> Result<usize> {
+    let count = self.read_u32::<LE>()?;
+    if count as usize > self.limits.item_count {
+      return Err(Error::LimitExceeded(count.into(), self.limits.item_count));
+    }
+    Ok(count as usize)
+  }
+
+  pub(crate) fn read_text(&mut self, encoding: TextEncoding) -> Result<String> {
+    let size = length(self.read_i32::<LE>()?, self.limits.text_length)?;
+    let mut buf = Vec::new();
+    self.take(size as u64).read_to_end(&mut buf)?;
+    if buf.len() < size {
+      return Err(std::io::Error::from(std::io::ErrorKind::UnexpectedEof).into());
+    }
+    (match encoding {
+      TextEncoding::UTF8 => UTF_8.decode(&buf, DecoderTrap::Strict),
+      TextEncoding::UTF16LE => UTF_16LE.decode(&buf, DecoderTrap::Strict),
+    })
+    .map_err(Error::DecodeText)
+  }
+}
+
+// short skips read through the data instead, seeking would throw away the buffer of a BufReader
+const SEEK_THRESHOLD: u64 = 4096;
+
+impl<R: Read + Seek> Source<R> {
+  pub(crate) fn seek_to(&mut self, base: u64, offset: u64) -> Result<()> {
+    self.read.seek(SeekFrom::Start(base + offset))?;
+    self.location.offset = offset;
+    Ok(())
+  }
+
+  pub(crate) fn skip(&mut self, size: u64) -> Result<()> {
+    if size < SEEK_THRESHOLD {
+      if std::io::copy(&mut self.take(size), &mut std::io::sink())? < size {
+        return Err(std::io::Error::from(std::io::ErrorKind::UnexpectedEof).into());
+      }
+    } else {
+      // seeking past the end succeeds, so a truncated file is only noticed against its length
+      let position = self.read.seek(SeekFrom::Current(size as i64))?;
+      let end = self.read.seek(SeekFrom::End(0))?;
+      if position > end {
+        self.location.offset += size - (position - end);
+        return Err(std::io::Error::from(std::io::ErrorKind::UnexpectedEof).into());
+      }
+      self.read.seek(SeekFrom::Start(position))?;
+      self.location.offset += size;
+    }
+    Ok(())
+  }
+
+  pub(crate) fn skip_text(&mut self) -> Result<()> {
+    let size = length(self.read_i32::<LE>()?, self.limits.text_length)?;
+    self.skip(size as u64)
+  }
+}
+
+impl<R: Read> Read for Source<R> {
+  fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+    let size = self.read.read(buf)?;
+    self.location.offset += size as u64;
+    Ok(size)
+  }
+}
+
+fn length(length: i32, limit: usize) -> Result<usize> {
+  match usize::try_from(length) {
+    Err(_) => Err(Error::NegativeLength(length)),
+    Ok(length) if length > limit => Err(Error::LimitExceeded(length as u64, limit)),
+    Ok(length) => Ok(length),
+  }
+}
@@ -0,0 +1,423 @@
+use crate::{
+  pmx::bone::BoneFlags,
+  pmx::error::Section,
+  pmx::soft_body::SoftBodyFlags,
+  reader::{
+    helpers::{with_capacity, Source},
+    BoneReader, DisplayReader, HeaderReader, JointReader, Limits, MaterialReader, MorphReader,
+    RigidBodyReader, SoftBodyReader, SurfaceReader, TextureReader, VertexReader,
+  },
+  Error, IndexSize, Result, Settings,
+};
+use byteorder::{ReadBytesExt, LE};
+use enumflags2::BitFlags;
+use std::io::{Read, Seek};
+
+const SECTIONS: [Section; 10] = [
+  Section::Vertices,
+  Section::Surfaces,
+  Section::Textures,
+  Section::Materials,
+  Section::Bones,
+  Section::Morphs,
+  Section::DisplayFrames,
+  Section::RigidBodies,
+  Section::Joints,
+  Section::SoftBodies,
+];
+
+#[derive(Clone, Debug, Default)]
+struct Entry {
+  offset: u64,
+  end: u64,
+  count: i32,
+  // surfaces all have the same size, so their offsets are computed instead of stored
+  elements: Vec<u64>,
+}
+
+// offsets are relative to the start of the model, like the ones in error locations
+#[derive(Clone, Debug)]
+pub struct SectionIndex {
+  pub version: f32,
+  pub settings: Settings,
+  pub limits: Limits,
+  base: u64,
+  entries: Vec<Entry>,
+}
+
+impl SectionIndex {
+  pub fn scan<R: Read + Seek>(read: R) -> Result<SectionIndex> {
+    SectionIndex::scan_with_limits(read, Limits::default())
+  }
+
+  pub fn scan_with_limits<R: Read + Seek>(mut read: R, limits: Limits) -> Result<SectionIndex> {
+    let base = read.stream_position()?;
+    let header = HeaderReader::with_limits(read, limits)?;
+    let version = header.version;
+    let settings = header.settings;
+    let mut source = header.read;
+
+    let mut entries = vec![Entry::default()];
+    for &section in &SECTIONS {
+      let entry = if section == Section::SoftBodies && version < 2.1 {
+        let end = source.offset();
+        Entry {
+          offset: end,
+          end,
+          ..Entry::default()
+        }
+      } else {
+        scan_section(&mut source, &settings, section)?
+      };
+      entries.push(entry);
+    }
+
+    Ok(SectionIndex {
+      version,
+      settings,
+      limits,
+      base,
+      entries,
+    })
+  }
+
+  pub fn offset(&self, section: Section) -> u64 {
+    self.entries[section as usize].offset
+  }
+
+  pub fn end(&self, section: Section) -> u64 {
+    self.entries[section as usize].end
+  }
+
+  // surfaces are counted as triangles
+  pub fn count(&self, section: Section) -> usize {
+    let count = self.entries[section as usize].count as usize;
+    match section {
+      Section::Surfaces => count / 3,
+      _ => count,
+    }
+  }
+
+  pub fn element_offset(&self, section: Section, element: usize) -> Option<u64> {
+    let entry = &self.entries[section as usize];
+    match section {
+      Section::Surfaces if element < self.count(section) => {
+        Some(entry.offset + element as u64 * 3 * size(self.settings.vertex_index_size))
+      }
+      _ => entry.elements.get(element).copied(),
+    }
+  }
+
+  pub fn vertices<R: Read + Seek>(&self, read: R, first: usize) -> Result<VertexReader<R>> {
+    let (read, count, remaining) = self.open(read, Section::Vertices, first)?;
+    Ok(VertexReader {
+      version: self.version,
+      settings: self.settings,
+      count,
+      remaining,
+      read,
+      poison: false,
+    })
+  }
+
+  pub fn surfaces<R: Read + Seek>(&self, read: R, first: usize) -> Result<SurfaceReader<R>> {
+    let (read, count, remaining) = self.open(read, Section::Surfaces, first)?;
+    Ok(SurfaceReader {
+      version: self.version,
+      settings: self.settings,
+      count,
+      remaining,
+      read,
+      poison: false,
+    })
+  }
+
+  pub fn textures<R: Read + Seek>(&self, read: R, first: usize) -> Result<TextureReader<R>> {
+    let (read, count, remaining) = self.open(read, Section::Textures, first)?;
+    Ok(TextureReader {
+      version: self.version,
+      settings: self.settings,
+      count,
+      remaining,
+      read,
+      poison: false,
+    })
+  }
+
+  pub fn materials<R: Read + Seek>(&self, read: R, first: usize) -> Result<MaterialReader<R>> {
+    let (read, count, remaining) = self.open(read, Section::Materials, first)?;
+    Ok(MaterialReader {
+      version: self.version,
+      settings: self.settings,
+      count,
+      remaining,
+      read,
+      poison: false,
+    })
+  }
+
+  pub fn bones<R: Read + Seek>(&self, read: R, first: usize) -> Result<BoneReader<R>> {
+    let (read, count, remaining) = self.open(read, Section::Bones, first)?;
+    Ok(BoneReader {
+      version: self.version,
+      settings: self.settings,
+      count,
+      remaining,
+      read,
+      poison: false,
+    })
+  }
+
+  pub fn morphs<R: Read + Seek>(&self, read: R, first: usize) -> Result<MorphReader<R>> {
+    let (read, count, remaining) = self.open(read, Section::Morphs, first)?;
+    Ok(MorphReader {
+      version: self.version,
+      settings: self.settings,
+      count,
+      remaining,
+      read,
+      poison: false,
+    })
+  }
+
+  pub fn display_frames<R: Read + Seek>(&self, read: R, first: usize) -> Result<DisplayReader<R>> {
+    let (read, count, remaining) = self.open(read, Section::DisplayFrames, first)?;
+    Ok(DisplayReader {
+      version: self.version,
+      settings: self.settings,
+      count,
+      remaining,
+      read,
+      poison: false,
+    })
+  }
+
+  pub fn rigid_bodies<R: Read + Seek>(&self, read: R, first: usize) -> Result<RigidBodyReader<R>> {
+    let (read, count, remaining) = self.open(read, Section::RigidBodies, first)?;
+    Ok(RigidBodyReader {
+      version: self.version,
+      settings: self.settings,
+      count,
+      remaining,
+      read,
+      poison: false,
+    })
+  }
+
+  pub fn joints<R: Read + Seek>(&self, read: R, first: usize) -> Result<JointReader<R>> {
+    let (read, count, remaining) = self.open(read, Section::Joints, first)?;
+    Ok(JointReader {
+      version: self.version,
+      settings: self.settings,
+      count,
+      remaining,
+      read,
+      poison: false,
+    })
+  }
+
+  pub fn soft_bodies<R: Read + Seek>(&self, read: R, first: usize) -> Result<SoftBodyReader<R>> {
+    let (read, count, remaining) = self.open(read, Section::SoftBodies, first)?;
+    Ok(SoftBodyReader {
+      version: self.version,
+      settings: self.settings,
+      count,
+      remaining,
+      read,
+      poison: false,
+    })
+  }
+
+  // starting right after the last element gives an empty reader, which can still move on to the next section
+  fn open<R: Read + Seek>(
+    &self,
+    read: R,
+    section: Section,
+    first: usize,
+  ) -> Result<(Source<R>, i32, i32)> {
+    let entry = &self.entries[section as usize];
+    let offset = match self.element_offset(section, first) {
+      Some(offset) => offset,
+      None if first == self.count(section) => entry.end,
+      None => return Err(Error::InvalidElementIndex(section, first)),
+    };
+    let consumed = match section {
+      Section::Surfaces => first * 3,
+      _ => first,
+    };
+
+    let mut source = Source::new(read, self.limits);
+    source.section(section);
+    source.seek_to(self.base, offset)?;
+
+    Ok((source, entry.count, entry.count - consumed as i32))
+  }
+}
+
+fn size(size: IndexSize) -> u64 {
+  size as u64
+}
+
+fn scan_section<R: Read + Seek>(
+  source: &mut Source<R>,
+  settings: &Settings,
+  section: Section,
+) -> Result<Entry> {
+  let count = source.read_count(section)?;
+  let offset = source.offset();
+  let mut elements = Vec::new();
+
+  if section == Section::Surfaces {
+    let surfaces = count as u64 * size(settings.vertex_index_size);
+    source.skip(surfaces).map_err(|e| source.locate(e))?;
+  } else {
+    elements = with_capacity(count as usize);
+    for i in 0..count {
+      source.element(i);
+      elements.push(source.offset());
+      skip_element(source, settings, section).map_err(|e| source.locate(e))?;
+    }
+  }
+
+  Ok(Entry {
+    offset,
+    end: source.offset(),
+    count,
+    elements,
+  })
+}
+
+fn skip_element<R: Read + Seek>(
+  source: &mut Source<R>,
+  settings: &Settings,
+  section: Section,
+) -> Result<()> {
+  let vertex = size(settings.vertex_index_size);
+  let texture = size(settings.texture_index_size);
+  let material = size(settings.material_index_size);
+  let bone = size(settings.bone_index_size);
+  let morph = size(settings.morph_index_size);
+  let rigid_body = size(settings.rigidbody_index_size);
+
+  match section {
+    Section::Header | Section::Surfaces => Ok(()),
+    Section::Vertices => {
+      source.skip(32 + 16 * u64::from(settings.additional_vec4_count))?;
+      let weights = match source.read_u8()? {
+        0 => bone,
+        1 => 2 * bone + 4,
+        2 => 4 * bone + 16,
+        3 => 2 * bone + 40,
+        4 => 4 * bone + 16,
+        e => return Err(Error::UnknownWeightType(e)),
+      };
+      source.skip(weights + 4)
+    }
+    Section::Textures => source.skip_text(),
+    Section::Materials => {
+      source.skip_text()?;
+      source.skip_text()?;
+      source.skip(66 + 2 * texture)?;
+      let toon = match source.read_u8()? {
+        0 => texture,
+        1 => 1,
+        e => return Err(Error::InvalidToonReference(e)),
+      };
+      source.skip(toon)?;
+      source.skip_text()?;
+      source.skip(4)
+    }
+    Section::Bones => {
+      source.skip_text()?;
+      source.skip_text()?;
+      source.skip(16 + bone)?;
+      let bits = source.read_u16::<LE>()?;
+      let flags =
+        BitFlags::<BoneFlags>::from_bits(bits).map_err(|_| Error::UnknownBoneFlags(bits))?;
+
+      let mut optional = if flags.contains(BoneFlags::Connection) {
+        bone
+      } else {
+        12
+      };
+      if flags.intersects(BoneFlags::AddRotation | BoneFlags::AddMovement) {
+        optional += bone + 4;
+      }
+      if flags.contains(BoneFlags::FixedAxis) {
+        optional += 12;
+      }
+      if flags.contains(BoneFlags::LocalAxis) {
+        optional += 24;
+      }
+      if flags.contains(BoneFlags::ExternalParentTransform) {
+        optional += 4;
+      }
+      source.skip(optional)?;
+
+      if flags.contains(BoneFlags::InverseKinematics) {
+        source.skip(bone + 8)?;
+        for i in 0..source.read_item_count()? {
+          source.item(i);
+          source.skip(bone)?;
+          if source.read_u8()? != 0 {
+            source.skip(24)?;
+          }
+        }
+      }
+      Ok(())
+    }
+    Section::Morphs => {
+      source.skip_text()?;
+      source.skip_text()?;
+      source.skip(1)?;
+      let offset = match source.read_u8()? {
+        0 | 9 => morph + 4,
+        1 => vertex + 12,
+        2 => bone + 28,
+        3..=7 => vertex + 16,
+        8 => material + 113,
+        10 => rigid_body + 25,
+        e => return Err(Error::InvalidMorphType(e)),
+      };
+      let count = source.read_item_count()?;
+      source.skip(count as u64 * offset)
+    }
+    Section::DisplayFrames => {
+      source.skip_text()?;
+      source.skip_text()?;
+      source.skip(1)?;
+      for i in 0..source.read_item_count()? {
+        source.item(i);
+        let frame = match source.read_u8()? {
+          0 => bone,
+          1 => morph,
+          e => return Err(Error::InvalidFrameType(e)),
+        };
+        source.skip(frame)?;
+      }
+      Ok(())
+    }
+    Section::RigidBodies => {
+      source.skip_text()?;
+      source.skip_text()?;
+      source.skip(bone + 61)
+    }
+    Section::Joints => {
+      source.skip_text()?;
+      source.skip_text()?;
+      source.skip(97 + 2 * rigid_body)
+    }
+    Section::SoftBodies => {
+      source.skip_text()?;
+      source.skip_text()?;
+      source.skip(material + 4)?;
+      let bits = source.read_u8()?;
+      BitFlags::<SoftBodyFlags>::from_bits(bits).map_err(|_| Error::UnknownSoftBodyFlags(bits))?;
+      source.skip(120)?;
+      let count = source.read_item_count()?;
+      source.skip(count as u64 * (rigid_body + vertex + 1))?;
+      let count = source.read_item_count()?;
+      source.skip(count as u64 * vertex)
+    }
+  }
+}
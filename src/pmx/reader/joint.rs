@@ -1,30 +1,38 @@
 use crate::{
+  pmx::error::Section,
   pmx::joint::*,
-  reader::{helpers::ReadHelpers, RigidBodyReader},
-  Config, DefaultConfig, Result, Settings,
+  reader::{
+    helpers::{ReadHelpers, Source},
+    RigidBodyReader,
+  },
+  Config, DefaultConfig, Error, Result, Settings,
 };
-use byteorder::{ReadBytesExt, LE};
+use byteorder::ReadBytesExt;
 use std::convert::TryFrom;
 use std::io::Read;
 use std::marker::PhantomData;
 
 pub struct JointReader<R> {
+  pub version: f32,
   pub settings: Settings,
   pub count: i32,
   pub remaining: i32,
-  pub(crate) read: R,
+  pub(crate) read: Source<R>,
   pub(crate) poison: bool,
 }
 
 impl<R: Read> JointReader<R> {
   pub fn new(mut r: RigidBodyReader<R>) -> Result<JointReader<R>> {
-    assert!(!r.poison);
+    if r.poison {
+      return Err(Error::Poisoned);
+    }
     while r.remaining > 0 {
       r.next::<DefaultConfig>()?;
     }
-    let count = r.read.read_i32::<LE>()?;
+    let count = r.read.read_count(Section::Joints)?;
 
     Ok(JointReader {
+      version: r.version,
       settings: r.settings,
       count,
       remaining: count,
@@ -34,12 +42,14 @@ impl<R: Read> JointReader<R> {
   }
 
   pub fn next<C: Config>(&mut self) -> Result<Option<Joint<C>>> {
-    assert!(!self.poison);
+    if self.poison {
+      return Err(Error::Poisoned);
+    }
     let result = self.next_impl::<C>();
     if result.is_err() {
       self.poison = true;
     }
-    result
+    result.map_err(|e| self.read.locate(e))
   }
 
   fn next_impl<C: Config>(&mut self) -> Result<Option<Joint<C>>> {
@@ -47,10 +57,14 @@ impl<R: Read> JointReader<R> {
       return Ok(None);
     }
 
+    self.read.element(self.count - self.remaining);
     self.remaining -= 1;
 
+    let local_name = self.read.read_text(self.settings.text_encoding)?;
+    self.read.name(&local_name);
+
     Ok(Some(Joint {
-      local_name: self.read.read_text(self.settings.text_encoding)?,
+      local_name,
       universal_name: self.read.read_text(self.settings.text_encoding)?,
       joint_type: JointType::try_from(self.read.read_u8()?)?,
       rigid_body_a: self.read.read_index(self.settings.rigidbody_index_size)?,
@@ -66,7 +80,7 @@ impl<R: Read> JointReader<R> {
     }))
   }
 
-  pub fn iter<C>(&mut self) -> JointIterator<R, C> {
+  pub fn iter<C>(&mut self) -> JointIterator<'_, R, C> {
     JointIterator {
       reader: self,
       phantom: PhantomData,
@@ -0,0 +1,20 @@
+// upper bounds for lengths read from the file, so corrupt or hostile input can not exhaust memory
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Limits {
+  // bytes in a single encoded text
+  pub text_length: usize,
+  // elements in a single section, like vertices or morphs
+  pub section_length: usize,
+  // nested entries in a single element, like morph offsets, display frames or IK links
+  pub item_count: usize,
+}
+
+impl Default for Limits {
+  fn default() -> Self {
+    Limits {
+      text_length: 1 << 20,
+      section_length: 1 << 24,
+      item_count: 1 << 24,
+    }
+  }
+}
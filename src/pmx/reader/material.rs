@@ -1,6 +1,10 @@
 use crate::{
+  pmx::error::Section,
   pmx::material::*,
-  reader::{helpers::ReadHelpers, TextureReader},
+  reader::{
+    helpers::{ReadHelpers, Source},
+    TextureReader,
+  },
   Config, DefaultConfig, Error, Result, Settings,
 };
 use byteorder::{ReadBytesExt, LE};
@@ -10,22 +14,26 @@ use std::io::Read;
 use std::marker::PhantomData;
 
 pub struct MaterialReader<R> {
+  pub version: f32,
   pub settings: Settings,
   pub count: i32,
   pub remaining: i32,
-  pub(crate) read: R,
+  pub(crate) read: Source<R>,
   pub(crate) poison: bool,
 }
 
 impl<R: Read> MaterialReader<R> {
   pub fn new(mut t: TextureReader<R>) -> Result<MaterialReader<R>> {
-    assert!(!t.poison);
+    if t.poison {
+      return Err(Error::Poisoned);
+    }
     while t.remaining > 0 {
       t.next()?;
     }
-    let count = t.read.read_i32::<LE>()?;
+    let count = t.read.read_count(Section::Materials)?;
 
     Ok(MaterialReader {
+      version: t.version,
       settings: t.settings,
       count,
       remaining: count,
@@ -35,12 +43,14 @@ impl<R: Read> MaterialReader<R> {
   }
 
   pub fn next<C: Config>(&mut self) -> Result<Option<Material<C>>> {
-    assert!(!self.poison);
+    if self.poison {
+      return Err(Error::Poisoned);
+    }
     let result = self.next_impl::<C>();
     if result.is_err() {
       self.poison = true;
     }
-    result
+    result.map_err(|e| self.read.locate(e))
   }
 
   fn next_impl<C: Config>(&mut self) -> Result<Option<Material<C>>> {
@@ -48,16 +58,23 @@ impl<R: Read> MaterialReader<R> {
       return Ok(None);
     }
 
+    self.read.element(self.count - self.remaining);
     self.remaining -= 1;
 
+    let local_name = self.read.read_text(self.settings.text_encoding)?;
+    self.read.name(&local_name);
+
     Ok(Some(Material {
-      local_name: self.read.read_text(self.settings.text_encoding)?,
+      local_name,
       universal_name: self.read.read_text(self.settings.text_encoding)?,
       diffuse_color: self.read.read_vec4::<C>()?,
       specular_color: self.read.read_vec3::<C>()?,
       specular_strength: self.read.read_f32::<LE>()?,
       ambient_color: self.read.read_vec3::<C>()?,
-      draw_flags: BitFlags::from_bits(self.read.read_u8()?).unwrap(),
+      draw_flags: {
+        let bits = self.read.read_u8()?;
+        BitFlags::from_bits(bits).map_err(|_| Error::UnknownDrawingFlags(bits))?
+      },
       edge_color: self.read.read_vec4::<C>()?,
       edge_scale: self.read.read_f32::<LE>()?,
       texture_index: self.read.read_index(self.settings.texture_index_size)?,
@@ -73,7 +90,7 @@ impl<R: Read> MaterialReader<R> {
     }))
   }
 
-  pub fn iter<C>(&mut self) -> MaterialIterator<R, C> {
+  pub fn iter<C>(&mut self) -> MaterialIterator<'_, R, C> {
     MaterialIterator {
       reader: self,
       phantom: PhantomData,
@@ -1,6 +1,10 @@
 use crate::{
+  pmx::error::Section,
   pmx::morph::*,
-  reader::{helpers::ReadHelpers, BoneReader},
+  reader::{
+    helpers::{with_capacity, ReadHelpers, Source},
+    BoneReader,
+  },
   Config, DefaultConfig, Error, Result, Settings,
 };
 use byteorder::{ReadBytesExt, LE};
@@ -9,22 +13,26 @@ use std::io::Read;
 use std::marker::PhantomData;
 
 pub struct MorphReader<R> {
+  pub version: f32,
   pub settings: Settings,
   pub count: i32,
   pub remaining: i32,
-  pub(crate) read: R,
+  pub(crate) read: Source<R>,
   pub(crate) poison: bool,
 }
 
 impl<R: Read> MorphReader<R> {
   pub fn new(mut b: BoneReader<R>) -> Result<MorphReader<R>> {
-    assert!(!b.poison);
+    if b.poison {
+      return Err(Error::Poisoned);
+    }
     while b.remaining > 0 {
       b.next::<DefaultConfig>()?;
     }
-    let count = b.read.read_i32::<LE>()?;
+    let count = b.read.read_count(Section::Morphs)?;
 
     Ok(MorphReader {
+      version: b.version,
       settings: b.settings,
       count,
       remaining: count,
@@ -34,12 +42,14 @@ impl<R: Read> MorphReader<R> {
   }
 
   pub fn next<C: Config>(&mut self) -> Result<Option<Morph<C>>> {
-    assert!(!self.poison);
+    if self.poison {
+      return Err(Error::Poisoned);
+    }
     let result = self.next_impl::<C>();
     if result.is_err() {
       self.poison = true;
     }
-    result
+    result.map_err(|e| self.read.locate(e))
   }
 
   fn next_impl<C: Config>(&mut self) -> Result<Option<Morph<C>>> {
@@ -47,13 +57,16 @@ impl<R: Read> MorphReader<R> {
       return Ok(None);
     }
 
+    self.read.element(self.count - self.remaining);
     self.remaining -= 1;
 
     let local_name = self.read.read_text(self.settings.text_encoding)?;
+
+    self.read.name(&local_name);
     let universal_name = self.read.read_text(self.settings.text_encoding)?;
     let panel = Panel::from(self.read.read_u8()?);
     let morph_type = self.read.read_u8()?;
-    let morph_count = self.read.read_u32::<LE>()?;
+    let morph_count = self.read.read_item_count()?;
 
     let offsets = match morph_type {
       0 => Offsets::Group(self.next_morph_offsets(morph_count)?),
@@ -78,17 +91,18 @@ impl<R: Read> MorphReader<R> {
     }))
   }
 
-  pub fn iter<C>(&mut self) -> MorphIterator<R, C> {
+  pub fn iter<C>(&mut self) -> MorphIterator<'_, R, C> {
     MorphIterator {
       reader: self,
       phantom: PhantomData,
     }
   }
 
-  fn next_morph_offsets<C: Config>(&mut self, count: u32) -> Result<Vec<GroupOffset<C>>> {
-    let mut offsets = Vec::with_capacity(count as usize);
+  fn next_morph_offsets<C: Config>(&mut self, count: usize) -> Result<Vec<GroupOffset<C>>> {
+    let mut offsets = with_capacity(count);
 
-    for _ in 0..count {
+    for i in 0..count {
+      self.read.item(i);
       offsets.push(GroupOffset {
         morph: self.read.read_index(self.settings.morph_index_size)?,
         influence: self.read.read_f32::<LE>()?,
@@ -98,10 +112,11 @@ impl<R: Read> MorphReader<R> {
     Ok(offsets)
   }
 
-  fn next_vertex_offsets<C: Config>(&mut self, count: u32) -> Result<Vec<VertexOffset<C>>> {
-    let mut offsets = Vec::with_capacity(count as usize);
+  fn next_vertex_offsets<C: Config>(&mut self, count: usize) -> Result<Vec<VertexOffset<C>>> {
+    let mut offsets = with_capacity(count);
 
-    for _ in 0..count {
+    for i in 0..count {
+      self.read.item(i);
       offsets.push(VertexOffset {
         vertex: self
           .read
@@ -113,10 +128,11 @@ impl<R: Read> MorphReader<R> {
     Ok(offsets)
   }
 
-  fn next_bone_offsets<C: Config>(&mut self, count: u32) -> Result<Vec<BoneOffset<C>>> {
-    let mut offsets = Vec::with_capacity(count as usize);
+  fn next_bone_offsets<C: Config>(&mut self, count: usize) -> Result<Vec<BoneOffset<C>>> {
+    let mut offsets = with_capacity(count);
 
-    for _ in 0..count {
+    for i in 0..count {
+      self.read.item(i);
       offsets.push(BoneOffset {
         bone: self.read.read_index(self.settings.bone_index_size)?,
         translation: self.read.read_vec3::<C>()?,
@@ -127,10 +143,11 @@ impl<R: Read> MorphReader<R> {
     Ok(offsets)
   }
 
-  fn next_uv_offsets<C: Config>(&mut self, count: u32) -> Result<Vec<UVOffset<C>>> {
-    let mut offsets = Vec::with_capacity(count as usize);
+  fn next_uv_offsets<C: Config>(&mut self, count: usize) -> Result<Vec<UVOffset<C>>> {
+    let mut offsets = with_capacity(count);
 
-    for _ in 0..count {
+    for i in 0..count {
+      self.read.item(i);
       offsets.push(UVOffset {
         vertex: self
           .read
@@ -142,10 +159,11 @@ impl<R: Read> MorphReader<R> {
     Ok(offsets)
   }
 
-  fn next_material_offsets<C: Config>(&mut self, count: u32) -> Result<Vec<MaterialOffset<C>>> {
-    let mut offsets = Vec::with_capacity(count as usize);
+  fn next_material_offsets<C: Config>(&mut self, count: usize) -> Result<Vec<MaterialOffset<C>>> {
+    let mut offsets = with_capacity(count);
 
-    for _ in 0..count {
+    for i in 0..count {
+      self.read.item(i);
       offsets.push(MaterialOffset {
         material: self.read.read_index(self.settings.material_index_size)?,
         method: OffsetMethod::try_from(self.read.read_u8()?)?,
@@ -164,10 +182,11 @@ impl<R: Read> MorphReader<R> {
     Ok(offsets)
   }
 
-  fn next_impulse_offsets<C: Config>(&mut self, count: u32) -> Result<Vec<ImpulseOffset<C>>> {
-    let mut offsets = Vec::with_capacity(count as usize);
+  fn next_impulse_offsets<C: Config>(&mut self, count: usize) -> Result<Vec<ImpulseOffset<C>>> {
+    let mut offsets = with_capacity(count);
 
-    for _ in 0..count {
+    for i in 0..count {
+      self.read.item(i);
       offsets.push(ImpulseOffset {
         rigid_body: self.read.read_index(self.settings.rigidbody_index_size)?,
         local: self.read.read_u8()? != 0,
@@ -1,7 +1,11 @@
 use crate::{
+  pmx::error::Section,
   pmx::rigid_body::*,
-  reader::{helpers::ReadHelpers, DisplayReader},
-  Config, DefaultConfig, Result, Settings,
+  reader::{
+    helpers::{ReadHelpers, Source},
+    DisplayReader,
+  },
+  Config, DefaultConfig, Error, Result, Settings,
 };
 use byteorder::{ReadBytesExt, LE};
 use std::convert::TryFrom;
@@ -9,22 +13,26 @@ use std::io::Read;
 use std::marker::PhantomData;
 
 pub struct RigidBodyReader<R> {
+  pub version: f32,
   pub settings: Settings,
   pub count: i32,
   pub remaining: i32,
-  pub(crate) read: R,
+  pub(crate) read: Source<R>,
   pub(crate) poison: bool,
 }
 
 impl<R: Read> RigidBodyReader<R> {
   pub fn new(mut d: DisplayReader<R>) -> Result<RigidBodyReader<R>> {
-    assert!(!d.poison);
+    if d.poison {
+      return Err(Error::Poisoned);
+    }
     while d.remaining > 0 {
       d.next::<DefaultConfig>()?;
     }
-    let count = d.read.read_i32::<LE>()?;
+    let count = d.read.read_count(Section::RigidBodies)?;
 
     Ok(RigidBodyReader {
+      version: d.version,
       settings: d.settings,
       count,
       remaining: count,
@@ -34,12 +42,14 @@ impl<R: Read> RigidBodyReader<R> {
   }
 
   pub fn next<C: Config>(&mut self) -> Result<Option<RigidBody<C>>> {
-    assert!(!self.poison);
+    if self.poison {
+      return Err(Error::Poisoned);
+    }
     let result = self.next_impl::<C>();
     if result.is_err() {
       self.poison = true;
     }
-    result
+    result.map_err(|e| self.read.locate(e))
   }
 
   fn next_impl<C: Config>(&mut self) -> Result<Option<RigidBody<C>>> {
@@ -47,10 +57,14 @@ impl<R: Read> RigidBodyReader<R> {
       return Ok(None);
     }
 
+    self.read.element(self.count - self.remaining);
     self.remaining -= 1;
 
+    let local_name = self.read.read_text(self.settings.text_encoding)?;
+    self.read.name(&local_name);
+
     Ok(Some(RigidBody {
-      local_name: self.read.read_text(self.settings.text_encoding)?,
+      local_name,
       universal_name: self.read.read_text(self.settings.text_encoding)?,
       bone_index: self.read.read_index(self.settings.bone_index_size)?,
       group_id: self.read.read_u8()?,
@@ -68,7 +82,7 @@ impl<R: Read> RigidBodyReader<R> {
     }))
   }
 
-  pub fn iter<C>(&mut self) -> RigidBodyIterator<R, C> {
+  pub fn iter<C>(&mut self) -> RigidBodyIterator<'_, R, C> {
     RigidBodyIterator {
       reader: self,
       phantom: PhantomData,
@@ -0,0 +1,199 @@
+use crate::{
+  pmx::error::Section,
+  pmx::soft_body::*,
+  reader::{
+    helpers::{with_capacity, ReadHelpers, Source},
+    JointReader,
+  },
+  Config, DefaultConfig, Error, Result, Settings,
+};
+use byteorder::{ReadBytesExt, LE};
+use enumflags2::BitFlags;
+use std::convert::TryFrom;
+use std::io::Read;
+use std::marker::PhantomData;
+
+pub struct SoftBodyReader<R> {
+  pub version: f32,
+  pub settings: Settings,
+  pub count: i32,
+  pub remaining: i32,
+  pub(crate) read: Source<R>,
+  pub(crate) poison: bool,
+}
+
+impl<R: Read> SoftBodyReader<R> {
+  // soft bodies were added in 2.1, earlier models end after the joints
+  pub fn new(mut j: JointReader<R>) -> Result<SoftBodyReader<R>> {
+    if j.poison {
+      return Err(Error::Poisoned);
+    }
+    while j.remaining > 0 {
+      j.next::<DefaultConfig>()?;
+    }
+    let count = if j.version >= 2.1 {
+      j.read.read_count(Section::SoftBodies)?
+    } else {
+      0
+    };
+
+    Ok(SoftBodyReader {
+      version: j.version,
+      settings: j.settings,
+      count,
+      remaining: count,
+      read: j.read,
+      poison: false,
+    })
+  }
+
+  pub fn next<C: Config>(&mut self) -> Result<Option<SoftBody<C>>> {
+    if self.poison {
+      return Err(Error::Poisoned);
+    }
+    let result = self.next_impl::<C>();
+    if result.is_err() {
+      self.poison = true;
+    }
+    result.map_err(|e| self.read.locate(e))
+  }
+
+  fn next_impl<C: Config>(&mut self) -> Result<Option<SoftBody<C>>> {
+    if self.remaining <= 0 {
+      return Ok(None);
+    }
+
+    self.read.element(self.count - self.remaining);
+    self.remaining -= 1;
+
+    let local_name = self.read.read_text(self.settings.text_encoding)?;
+    self.read.name(&local_name);
+    let universal_name = self.read.read_text(self.settings.text_encoding)?;
+    let shape = SoftBodyShape::try_from(self.read.read_u8()?)?;
+    let material_index = self.read.read_index(self.settings.material_index_size)?;
+    let group_id = self.read.read_u8()?;
+    let collision_mask = self.read.read_u16::<LE>()?;
+    let bits = self.read.read_u8()?;
+    let flags = BitFlags::from_bits(bits).map_err(|_| Error::UnknownSoftBodyFlags(bits))?;
+    let b_link_distance = self.read.read_i32::<LE>()?;
+    let cluster_count = self.read.read_i32::<LE>()?;
+    let total_mass = self.read.read_f32::<LE>()?;
+    let collision_margin = self.read.read_f32::<LE>()?;
+    let aero_model = AeroModel::try_from(self.read.read_i32::<LE>()?)?;
+
+    let config = SoftBodyConfig {
+      velocity_correction: self.read.read_f32::<LE>()?,
+      damping: self.read.read_f32::<LE>()?,
+      drag: self.read.read_f32::<LE>()?,
+      lift: self.read.read_f32::<LE>()?,
+      pressure: self.read.read_f32::<LE>()?,
+      volume_conservation: self.read.read_f32::<LE>()?,
+      dynamic_friction: self.read.read_f32::<LE>()?,
+      pose_matching: self.read.read_f32::<LE>()?,
+      rigid_contact_hardness: self.read.read_f32::<LE>()?,
+      kinetic_contact_hardness: self.read.read_f32::<LE>()?,
+      soft_contact_hardness: self.read.read_f32::<LE>()?,
+      anchor_hardness: self.read.read_f32::<LE>()?,
+    };
+    let cluster = SoftBodyCluster {
+      soft_rigid_hardness: self.read.read_f32::<LE>()?,
+      soft_kinetic_hardness: self.read.read_f32::<LE>()?,
+      soft_soft_hardness: self.read.read_f32::<LE>()?,
+      soft_rigid_impulse_split: self.read.read_f32::<LE>()?,
+      soft_kinetic_impulse_split: self.read.read_f32::<LE>()?,
+      soft_soft_impulse_split: self.read.read_f32::<LE>()?,
+    };
+    let iterations = SoftBodyIterations {
+      velocity: self.read.read_i32::<LE>()?,
+      position: self.read.read_i32::<LE>()?,
+      drift: self.read.read_i32::<LE>()?,
+      cluster: self.read.read_i32::<LE>()?,
+    };
+    let material = SoftBodyMaterial {
+      linear_stiffness: self.read.read_f32::<LE>()?,
+      area_stiffness: self.read.read_f32::<LE>()?,
+      volume_stiffness: self.read.read_f32::<LE>()?,
+    };
+
+    let count = self.read.read_item_count()?;
+    let mut anchors = with_capacity(count);
+    for i in 0..count {
+      self.read.item(i);
+      anchors.push(Anchor {
+        rigid_body: self.read.read_index(self.settings.rigidbody_index_size)?,
+        vertex: self
+          .read
+          .read_vertex_index(self.settings.vertex_index_size)?,
+        near_mode: self.read.read_u8()? != 0,
+      });
+    }
+
+    let count = self.read.read_item_count()?;
+    let mut pinned_vertices = with_capacity(count);
+    for i in 0..count {
+      self.read.item(i);
+      pinned_vertices.push(
+        self
+          .read
+          .read_vertex_index(self.settings.vertex_index_size)?,
+      );
+    }
+
+    Ok(Some(SoftBody {
+      local_name,
+      universal_name,
+      shape,
+      material_index,
+      group_id,
+      collision_mask,
+      flags,
+      b_link_distance,
+      cluster_count,
+      total_mass,
+      collision_margin,
+      aero_model,
+      config,
+      cluster,
+      iterations,
+      material,
+      anchors,
+      pinned_vertices,
+    }))
+  }
+
+  pub fn iter<C>(&mut self) -> SoftBodyIterator<'_, R, C> {
+    SoftBodyIterator {
+      reader: self,
+      phantom: PhantomData,
+    }
+  }
+}
+
+pub struct SoftBodyIterator<'a, R, C> {
+  reader: &'a mut SoftBodyReader<R>,
+  phantom: PhantomData<C>,
+}
+
+impl<R: Read, C: Config> Iterator for SoftBodyIterator<'_, R, C> {
+  type Item = Result<SoftBody<C>>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    self
+      .reader
+      .next()
+      .map_or_else(|e| Some(Err(e)), |v| v.map(Ok))
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    (
+      self.reader.remaining as usize,
+      Some(self.reader.remaining as usize),
+    )
+  }
+}
+
+impl<R: Read, C: Config> ExactSizeIterator for SoftBodyIterator<'_, R, C> {
+  fn len(&self) -> usize {
+    self.reader.remaining as usize
+  }
+}
@@ -1,28 +1,35 @@
 use crate::{
-  reader::{helpers::ReadHelpers, VertexReader},
-  Config, DefaultConfig, Result, Settings,
+  pmx::error::Section,
+  reader::{
+    helpers::{ReadHelpers, Source},
+    VertexReader,
+  },
+  Config, DefaultConfig, Error, Result, Settings,
 };
-use byteorder::{ReadBytesExt, LE};
 use std::io::Read;
 use std::marker::PhantomData;
 
 pub struct SurfaceReader<R> {
+  pub version: f32,
   pub settings: Settings,
   pub count: i32,
   pub remaining: i32,
-  pub(crate) read: R,
+  pub(crate) read: Source<R>,
   pub(crate) poison: bool,
 }
 
 impl<R: Read> SurfaceReader<R> {
   pub fn new(mut v: VertexReader<R>) -> Result<SurfaceReader<R>> {
-    assert!(!v.poison);
+    if v.poison {
+      return Err(Error::Poisoned);
+    }
     while v.remaining > 0 {
       v.next::<DefaultConfig>()?;
     }
-    let count = v.read.read_i32::<LE>()?;
+    let count = v.read.read_count(Section::Surfaces)?;
 
     Ok(SurfaceReader {
+      version: v.version,
       settings: v.settings,
       count,
       remaining: count,
@@ -32,12 +39,14 @@ impl<R: Read> SurfaceReader<R> {
   }
 
   pub fn next<C: Config>(&mut self) -> Result<Option<[C::VertexIndex; 3]>> {
-    assert!(!self.poison);
+    if self.poison {
+      return Err(Error::Poisoned);
+    }
     let result = self.next_impl::<C>();
     if result.is_err() {
       self.poison = true;
     }
-    result
+    result.map_err(|e| self.read.locate(e))
   }
 
   fn next_impl<C: Config>(&mut self) -> Result<Option<[C::VertexIndex; 3]>> {
@@ -45,6 +54,7 @@ impl<R: Read> SurfaceReader<R> {
       return Ok(None);
     }
 
+    self.read.element((self.count - self.remaining) / 3);
     self.remaining -= 3;
     Ok(Some([
       self
@@ -59,7 +69,7 @@ impl<R: Read> SurfaceReader<R> {
     ]))
   }
 
-  pub fn iter<I>(&mut self) -> SurfaceIterator<R, I> {
+  pub fn iter<I>(&mut self) -> SurfaceIterator<'_, R, I> {
     SurfaceIterator {
       reader: self,
       phantom: PhantomData,
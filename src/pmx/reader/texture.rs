@@ -1,27 +1,31 @@
 use crate::{
-  reader::{helpers::ReadHelpers, SurfaceReader},
-  DefaultConfig, Result, Settings,
+  pmx::error::Section,
+  reader::{helpers::Source, SurfaceReader},
+  DefaultConfig, Error, Result, Settings,
 };
-use byteorder::{ReadBytesExt, LE};
 use std::io::Read;
 
 pub struct TextureReader<R> {
+  pub version: f32,
   pub settings: Settings,
   pub count: i32,
   pub remaining: i32,
-  pub(crate) read: R,
+  pub(crate) read: Source<R>,
   pub(crate) poison: bool,
 }
 
 impl<R: Read> TextureReader<R> {
   pub fn new(mut s: SurfaceReader<R>) -> Result<TextureReader<R>> {
-    assert!(!s.poison);
+    if s.poison {
+      return Err(Error::Poisoned);
+    }
     while s.remaining > 0 {
       s.next::<DefaultConfig>()?;
     }
-    let count = s.read.read_i32::<LE>()?;
+    let count = s.read.read_count(Section::Textures)?;
 
     Ok(TextureReader {
+      version: s.version,
       settings: s.settings,
       count,
       remaining: count,
@@ -31,12 +35,14 @@ impl<R: Read> TextureReader<R> {
   }
 
   pub fn next(&mut self) -> Result<Option<String>> {
-    assert!(!self.poison);
+    if self.poison {
+      return Err(Error::Poisoned);
+    }
     let result = self.next_impl();
     if result.is_err() {
       self.poison = true;
     }
-    result
+    result.map_err(|e| self.read.locate(e))
   }
 
   fn next_impl(&mut self) -> Result<Option<String>> {
@@ -44,12 +50,13 @@ impl<R: Read> TextureReader<R> {
       return Ok(None);
     }
 
+    self.read.element(self.count - self.remaining);
     self.remaining -= 1;
 
     self.read.read_text(self.settings.text_encoding).map(Some)
   }
 
-  pub fn iter(&mut self) -> TextureIterator<R> {
+  pub fn iter(&mut self) -> TextureIterator<'_, R> {
     TextureIterator { reader: self }
   }
 }
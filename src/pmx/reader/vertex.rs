@@ -1,24 +1,30 @@
 use crate::{
+  pmx::error::Section,
   pmx::weight_deform::*,
-  reader::{helpers::ReadHelpers, HeaderReader},
+  reader::{
+    helpers::{ReadHelpers, Source},
+    HeaderReader,
+  },
   Config, DefaultConfig, Error, Result, Settings, Vertex,
 };
 use byteorder::{ReadBytesExt, LE};
+use std::io::Read;
 use std::marker::PhantomData;
-use std::{io::Read, usize};
 
 pub struct VertexReader<R> {
+  pub version: f32,
   pub settings: Settings,
   pub count: i32,
   pub remaining: i32,
-  pub(crate) read: R,
+  pub(crate) read: Source<R>,
   pub(crate) poison: bool,
 }
 
 impl<R: Read> VertexReader<R> {
   pub fn new(mut header: HeaderReader<R>) -> Result<VertexReader<R>> {
-    let count = header.read.read_i32::<LE>()?;
+    let count = header.read.read_count(Section::Vertices)?;
     Ok(VertexReader {
+      version: header.version,
       settings: header.settings,
       count,
       remaining: count,
@@ -28,18 +34,22 @@ impl<R: Read> VertexReader<R> {
   }
 
   pub fn next<C: Config>(&mut self) -> Result<Option<Vertex<C>>> {
-    assert!(!self.poison);
+    if self.poison {
+      return Err(Error::Poisoned);
+    }
     let result = self.next_impl::<C>();
     if result.is_err() {
       self.poison = true;
     }
-    result
+    result.map_err(|e| self.read.locate(e))
   }
 
   fn next_impl<C: Config>(&mut self) -> Result<Option<Vertex<C>>> {
     if self.remaining == 0 {
       return Ok(None);
     }
+
+    self.read.element(self.count - self.remaining);
     let position = self.read.read_vec3::<C>()?;
     let normal = self.read.read_vec3::<C>()?;
     let uv = self.read.read_vec2::<C>()?;
@@ -98,7 +108,7 @@ impl<R: Read> VertexReader<R> {
     }))
   }
 
-  pub fn iter<C>(&mut self) -> VertexIterator<R, C> {
+  pub fn iter<C>(&mut self) -> VertexIterator<'_, R, C> {
     VertexIterator {
       reader: self,
       phantom: PhantomData,
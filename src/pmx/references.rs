@@ -0,0 +1,222 @@
+use crate::{
+  pmx::bone::Connection, pmx::display::Frame, pmx::error::Section, pmx::material::Toon,
+  pmx::morph::Offsets, pmx::types::raw, pmx::types::resolve, Config, Error, Model, Result,
+  WeightDeform,
+};
+use std::convert::TryInto;
+
+// a reference from an element of one section to an index in another
+pub(crate) type Reference = (Section, usize, Section, i64);
+
+struct Counts {
+  counts: [usize; 11],
+  broken: Vec<Reference>,
+}
+
+impl Counts {
+  // -1 is only accepted where the format uses it for none
+  fn check<I: TryInto<i64> + Clone>(
+    &mut self,
+    section: Section,
+    element: usize,
+    target: Section,
+    index: &I,
+    optional: bool,
+  ) {
+    let value = raw(index);
+    let valid = (optional && value == -1) || resolve(index, self.counts[target as usize]).is_some();
+    if !valid {
+      self.broken.push((section, element, target, value));
+    }
+  }
+}
+
+impl<C: Config> Model<C> {
+  // reading does not look at where indices point, so this is left to callers that need it
+  pub fn check_references(&self) -> Result<()> {
+    match self.broken_references().into_iter().next() {
+      Some((section, element, target, index)) => {
+        Err(Error::IndexOutOfRange(section, element, target, index))
+      }
+      None => Ok(()),
+    }
+  }
+
+  pub(crate) fn broken_references(&self) -> Vec<Reference> {
+    let mut counts = [0; 11];
+    counts[Section::Vertices as usize] = self.vertices.len();
+    counts[Section::Surfaces as usize] = self.surfaces.len();
+    counts[Section::Textures as usize] = self.textures.len();
+    counts[Section::Materials as usize] = self.materials.len();
+    counts[Section::Bones as usize] = self.bones.len();
+    counts[Section::Morphs as usize] = self.morphs.len();
+    counts[Section::DisplayFrames as usize] = self.display_frames.len();
+    counts[Section::RigidBodies as usize] = self.rigid_bodies.len();
+    counts[Section::Joints as usize] = self.joints.len();
+    counts[Section::SoftBodies as usize] = self.soft_bodies.len();
+    let mut counts = Counts {
+      counts,
+      broken: Vec::new(),
+    };
+
+    for (i, vertex) in self.vertices.iter().enumerate() {
+      let bones = match &vertex.weight_deform {
+        WeightDeform::Bdef1(d) => vec![&d.bone_index],
+        WeightDeform::Bdef2(d) => vec![&d.bone_1_index, &d.bone_2_index],
+        WeightDeform::Bdef4(d) => vec![
+          &d.bone_1_index,
+          &d.bone_2_index,
+          &d.bone_3_index,
+          &d.bone_4_index,
+        ],
+        WeightDeform::Sdef(d) => vec![&d.bone_1_index, &d.bone_2_index],
+        WeightDeform::Qdef(d) => vec![
+          &d.bone_1_index,
+          &d.bone_2_index,
+          &d.bone_3_index,
+          &d.bone_4_index,
+        ],
+      };
+      for bone in bones {
+        counts.check(Section::Vertices, i, Section::Bones, bone, true);
+      }
+    }
+
+    for (i, surface) in self.surfaces.iter().enumerate() {
+      for vertex in surface {
+        counts.check(Section::Surfaces, i, Section::Vertices, vertex, false);
+      }
+    }
+
+    for (i, material) in self.materials.iter().enumerate() {
+      let section = Section::Materials;
+      counts.check(section, i, Section::Textures, &material.texture_index, true);
+      counts.check(
+        section,
+        i,
+        Section::Textures,
+        &material.environment_index,
+        true,
+      );
+      if let Toon::Texture(texture) = &material.toon {
+        counts.check(section, i, Section::Textures, texture, true);
+      }
+    }
+
+    for (i, bone) in self.bones.iter().enumerate() {
+      let section = Section::Bones;
+      counts.check(section, i, Section::Bones, &bone.parent, true);
+      if let Connection::Index(tail) = &bone.connection {
+        counts.check(section, i, Section::Bones, tail, true);
+      }
+      if let Some(additional) = &bone.additional {
+        counts.check(section, i, Section::Bones, &additional.parent, true);
+      }
+      if let Some(ik) = &bone.inverse_kinematics {
+        counts.check(section, i, Section::Bones, &ik.ik_bone, false);
+        for link in &ik.links {
+          counts.check(section, i, Section::Bones, &link.ik_bone, false);
+        }
+      }
+    }
+
+    for (i, morph) in self.morphs.iter().enumerate() {
+      let section = Section::Morphs;
+      match &morph.offsets {
+        Offsets::Group(offsets) | Offsets::Flip(offsets) => {
+          for offset in offsets {
+            counts.check(section, i, Section::Morphs, &offset.morph, false);
+          }
+        }
+        Offsets::Vertex(offsets) => {
+          for offset in offsets {
+            counts.check(section, i, Section::Vertices, &offset.vertex, false);
+          }
+        }
+        Offsets::Bone(offsets) => {
+          for offset in offsets {
+            counts.check(section, i, Section::Bones, &offset.bone, false);
+          }
+        }
+        Offsets::UV(offsets)
+        | Offsets::AdditionalUV1(offsets)
+        | Offsets::AdditionalUV2(offsets)
+        | Offsets::AdditionalUV3(offsets)
+        | Offsets::AdditionalUV4(offsets) => {
+          for offset in offsets {
+            counts.check(section, i, Section::Vertices, &offset.vertex, false);
+          }
+        }
+        // -1 applies the offset to every material
+        Offsets::Material(offsets) => {
+          for offset in offsets {
+            counts.check(section, i, Section::Materials, &offset.material, true);
+          }
+        }
+        Offsets::Impulse(offsets) => {
+          for offset in offsets {
+            counts.check(section, i, Section::RigidBodies, &offset.rigid_body, false);
+          }
+        }
+      }
+    }
+
+    for (i, display_frame) in self.display_frames.iter().enumerate() {
+      for frame in &display_frame.frames {
+        match frame {
+          Frame::Bone(bone) => counts.check(Section::DisplayFrames, i, Section::Bones, bone, false),
+          Frame::Morph(morph) => {
+            counts.check(Section::DisplayFrames, i, Section::Morphs, morph, false)
+          }
+        }
+      }
+    }
+
+    for (i, rigid_body) in self.rigid_bodies.iter().enumerate() {
+      counts.check(
+        Section::RigidBodies,
+        i,
+        Section::Bones,
+        &rigid_body.bone_index,
+        true,
+      );
+    }
+
+    for (i, joint) in self.joints.iter().enumerate() {
+      counts.check(
+        Section::Joints,
+        i,
+        Section::RigidBodies,
+        &joint.rigid_body_a,
+        false,
+      );
+      counts.check(
+        Section::Joints,
+        i,
+        Section::RigidBodies,
+        &joint.rigid_body_b,
+        false,
+      );
+    }
+
+    for (i, soft_body) in self.soft_bodies.iter().enumerate() {
+      let section = Section::SoftBodies;
+      counts.check(
+        section,
+        i,
+        Section::Materials,
+        &soft_body.material_index,
+        true,
+      );
+      for anchor in &soft_body.anchors {
+        counts.check(section, i, Section::RigidBodies, &anchor.rigid_body, false);
+        counts.check(section, i, Section::Vertices, &anchor.vertex, false);
+      }
+      for vertex in &soft_body.pinned_vertices {
+        counts.check(section, i, Section::Vertices, vertex, false);
+      }
+    }
+
+    counts.broken
+  }
+}
@@ -1,9 +1,12 @@
 use crate::{Config, Error};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 use std::convert::TryFrom;
 use std::fmt::{Debug, Display, Formatter};
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 #[repr(u8)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum ShapeType {
   Sphere = 0,
   Box = 1,
@@ -35,6 +38,7 @@ impl TryFrom<u8> for ShapeType {
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 #[repr(u8)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum PhysicsMode {
   Static = 0,
   Dynamic = 1,
@@ -64,6 +68,14 @@ impl TryFrom<u8> for PhysicsMode {
   }
 }
 
+#[cfg_attr(
+  feature = "serde",
+  derive(Serialize, Deserialize),
+  serde(bound(
+    serialize = "C::BoneIndex: Serialize, C::Vec3: Serialize",
+    deserialize = "C::BoneIndex: Deserialize<'de>, C::Vec3: Deserialize<'de>"
+  ))
+)]
 pub struct RigidBody<C: Config> {
   pub local_name: String,
   pub universal_name: String,
@@ -1,7 +1,92 @@
-use crate::pmx::types::*;
-use std::fmt::{Display, Formatter};
+use crate::{pmx::types::*, Error};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+use std::fmt::{Debug, Display, Formatter};
+use std::ops::Deref;
+
+// the globals count is a byte and the first 8 are always there
+const MAX_EXTRA_GLOBALS: usize = u8::MAX as usize - 8;
+
+// globals past the known ones, kept as they were read so they survive a round trip
+#[derive(Clone, Copy)]
+#[cfg_attr(
+  feature = "serde",
+  derive(Serialize, Deserialize),
+  serde(into = "Vec<u8>", try_from = "Vec<u8>")
+)]
+pub struct ExtraGlobals {
+  len: u8,
+  bytes: [u8; MAX_EXTRA_GLOBALS],
+}
+
+impl ExtraGlobals {
+  pub fn new() -> ExtraGlobals {
+    ExtraGlobals {
+      len: 0,
+      bytes: [0; MAX_EXTRA_GLOBALS],
+    }
+  }
+}
+
+impl Default for ExtraGlobals {
+  fn default() -> ExtraGlobals {
+    ExtraGlobals::new()
+  }
+}
+
+impl Deref for ExtraGlobals {
+  type Target = [u8];
+
+  fn deref(&self) -> &[u8] {
+    &self.bytes[..self.len as usize]
+  }
+}
+
+impl TryFrom<&[u8]> for ExtraGlobals {
+  type Error = Error;
+
+  fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+    if value.len() > MAX_EXTRA_GLOBALS {
+      return Err(Error::LimitExceeded(value.len() as u64, MAX_EXTRA_GLOBALS));
+    }
+    let mut extra = ExtraGlobals::new();
+    extra.bytes[..value.len()].copy_from_slice(value);
+    extra.len = value.len() as u8;
+    Ok(extra)
+  }
+}
+
+impl TryFrom<Vec<u8>> for ExtraGlobals {
+  type Error = Error;
+
+  fn try_from(value: Vec<u8>) -> Result<Self, Self::Error> {
+    ExtraGlobals::try_from(&value[..])
+  }
+}
+
+impl From<ExtraGlobals> for Vec<u8> {
+  fn from(value: ExtraGlobals) -> Self {
+    value.to_vec()
+  }
+}
+
+impl PartialEq for ExtraGlobals {
+  fn eq(&self, other: &ExtraGlobals) -> bool {
+    self[..] == other[..]
+  }
+}
+
+impl Eq for ExtraGlobals {}
+
+impl Debug for ExtraGlobals {
+  fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+    f.debug_list().entries(self.iter()).finish()
+  }
+}
 
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Settings {
   pub text_encoding: TextEncoding,
   pub additional_vec4_count: u8,
@@ -11,6 +96,45 @@ pub struct Settings {
   pub bone_index_size: IndexSize,
   pub morph_index_size: IndexSize,
   pub rigidbody_index_size: IndexSize,
+  pub extra_globals: ExtraGlobals,
+}
+
+impl Settings {
+  pub fn from_globals(globals: &[u8]) -> Result<Settings, Error> {
+    if globals.len() < 8 {
+      return Err(Error::GlobalsCountLessThan8(globals.len() as u8));
+    }
+    if globals[1] > 4 {
+      return Err(Error::WrongAdditionalVec4Count(globals[1].into()));
+    }
+
+    Ok(Settings {
+      text_encoding: TextEncoding::try_from(globals[0])?,
+      additional_vec4_count: globals[1],
+      vertex_index_size: IndexSize::try_from(globals[2])?,
+      texture_index_size: IndexSize::try_from(globals[3])?,
+      material_index_size: IndexSize::try_from(globals[4])?,
+      bone_index_size: IndexSize::try_from(globals[5])?,
+      morph_index_size: IndexSize::try_from(globals[6])?,
+      rigidbody_index_size: IndexSize::try_from(globals[7])?,
+      extra_globals: ExtraGlobals::try_from(&globals[8..])?,
+    })
+  }
+
+  pub fn globals(&self) -> Vec<u8> {
+    let mut globals = vec![
+      self.text_encoding as u8,
+      self.additional_vec4_count,
+      self.vertex_index_size as u8,
+      self.texture_index_size as u8,
+      self.material_index_size as u8,
+      self.bone_index_size as u8,
+      self.morph_index_size as u8,
+      self.rigidbody_index_size as u8,
+    ];
+    globals.extend_from_slice(&self.extra_globals);
+    globals
+  }
 }
 
 impl Display for Settings {
@@ -27,6 +151,10 @@ material index: {}, bone index: {}, morph index: {}, rigidbody index: {}",
       self.bone_index_size,
       self.morph_index_size,
       self.rigidbody_index_size
-    )
+    )?;
+    if !self.extra_globals.is_empty() {
+      writeln!(f, "extra globals: {:?}", self.extra_globals)?;
+    }
+    Ok(())
   }
 }
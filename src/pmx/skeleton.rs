@@ -0,0 +1,387 @@
+use crate::math::{self, Mat4, Quat, Vec3};
+use crate::pmx::bone::{BoneFlags, InverseKinematics};
+use crate::pmx::ik::{self, Chain, Link};
+use crate::pmx::types::{raw, resolve};
+use crate::{Bone, Config, Error, Result};
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Transform {
+  pub translation: Vec3,
+  pub rotation: Quat,
+}
+
+impl Default for Transform {
+  fn default() -> Self {
+    Transform {
+      translation: [0.0; 3],
+      rotation: math::QUAT_IDENTITY,
+    }
+  }
+}
+
+#[derive(Clone, Debug)]
+struct Append {
+  parent: usize,
+  rate: f32,
+  rotation: bool,
+  translation: bool,
+  local: bool,
+}
+
+#[derive(Clone, Debug)]
+struct Node {
+  parent: Option<usize>,
+  position: Vec3,
+  transform_level: i32,
+  after_physics: bool,
+  append: Option<Append>,
+  fixed_axis: Option<Vec3>,
+  local_axes: Option<[Vec3; 3]>,
+  ik: Option<Chain>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Skeleton {
+  nodes: Vec<Node>,
+  order: Vec<usize>,
+}
+
+impl Skeleton {
+  pub fn new<C: Config>(bones: &[Bone<C>]) -> Result<Skeleton> {
+    let mut nodes = Vec::with_capacity(bones.len());
+
+    for (i, bone) in bones.iter().enumerate() {
+      let parent = match (resolve(&bone.parent, bones.len()), raw(&bone.parent)) {
+        (Some(p), _) => Some(p),
+        (None, -1) => None,
+        (None, parent) => return Err(Error::InvalidBoneParent(i, parent)),
+      };
+
+      let flags = bone.bone_flags;
+      let append = match &bone.additional {
+        Some(additional)
+          if flags.contains(BoneFlags::AddRotation) || flags.contains(BoneFlags::AddMovement) =>
+        {
+          Some(Append {
+            parent: index::<C>(bones.len(), &additional.parent)?,
+            rate: additional.rate,
+            rotation: flags.contains(BoneFlags::AddRotation),
+            translation: flags.contains(BoneFlags::AddMovement),
+            local: flags.contains(BoneFlags::AddLocalDeform),
+          })
+        }
+        _ => None,
+      };
+
+      nodes.push(Node {
+        parent,
+        position: math::vec3(&bone.position),
+        transform_level: bone.transform_level,
+        after_physics: flags.contains(BoneFlags::PhysicalTransform),
+        append,
+        fixed_axis: bone
+          .fixed_axis
+          .as_ref()
+          .map(|a| math::normalize(math::vec3(a))),
+        local_axes: bone.local_axis.as_ref().map(|a| {
+          let x = math::normalize(math::vec3(&a.x));
+          let y = math::normalize(math::cross(math::vec3(&a.z), x));
+          [x, y, math::cross(x, y)]
+        }),
+        ik: None,
+      });
+    }
+
+    let order = sort(&nodes)?;
+
+    for (i, bone) in bones.iter().enumerate() {
+      if let Some(ik) = &bone.inverse_kinematics {
+        nodes[i].ik = Some(chain(&nodes, ik)?);
+      }
+    }
+
+    Ok(Skeleton { nodes, order })
+  }
+
+  pub fn len(&self) -> usize {
+    self.nodes.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.nodes.is_empty()
+  }
+
+  pub fn order(&self) -> &[usize] {
+    &self.order
+  }
+
+  pub fn parent(&self, bone: usize) -> Option<usize> {
+    self.nodes[bone].parent
+  }
+
+  pub fn position(&self, bone: usize) -> Vec3 {
+    self.nodes[bone].position
+  }
+
+  pub fn is_ik(&self, bone: usize) -> bool {
+    self.nodes[bone].ik.is_some()
+  }
+
+  pub fn fixed_axis(&self, bone: usize) -> Option<Vec3> {
+    self.nodes[bone].fixed_axis
+  }
+
+  pub fn local_axes(&self, bone: usize) -> Option<[Vec3; 3]> {
+    self.nodes[bone].local_axes
+  }
+
+  pub fn world_axes(&self, bone: usize, world: &[Mat4]) -> [Vec3; 3] {
+    let axes =
+      self.nodes[bone]
+        .local_axes
+        .unwrap_or([[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]]);
+    let rotate = |axis| math::normalize(math::mat4_transform_vector(&world[bone], axis));
+    [rotate(axes[0]), rotate(axes[1]), rotate(axes[2])]
+  }
+
+  pub fn from_local_axes(&self, bone: usize, rotation: Quat) -> Quat {
+    match self.nodes[bone].local_axes {
+      Some([x, y, z]) => {
+        let mut frame = math::IDENTITY;
+        frame[0][..3].copy_from_slice(&x);
+        frame[1][..3].copy_from_slice(&y);
+        frame[2][..3].copy_from_slice(&z);
+        let frame = math::quat_from_mat4(&frame);
+        math::quat_mul(math::quat_mul(frame, rotation), math::quat_conjugate(frame))
+      }
+      None => rotation,
+    }
+  }
+
+  pub fn bind_world(&self) -> Vec<Mat4> {
+    self
+      .nodes
+      .iter()
+      .map(|n| math::mat4_from_rotation_translation(math::QUAT_IDENTITY, n.position))
+      .collect()
+  }
+
+  pub fn local(&self, bone: usize, transform: &Transform) -> Mat4 {
+    let node = &self.nodes[bone];
+    let offset = match node.parent {
+      Some(p) => math::sub(node.position, self.nodes[p].position),
+      None => node.position,
+    };
+
+    math::mat4_from_rotation_translation(
+      transform.rotation,
+      math::add(offset, transform.translation),
+    )
+  }
+
+  pub fn world(&self, locals: &[Transform]) -> Vec<Mat4> {
+    let locals = self.locals(locals);
+    let mut appended = vec![Transform::default(); self.nodes.len()];
+    let mut world = vec![math::IDENTITY; self.nodes.len()];
+
+    self.refresh(&self.order, &locals, &mut appended, &mut world);
+
+    world
+  }
+
+  pub fn evaluate(&self, locals: &[Transform]) -> Vec<Mat4> {
+    let mut locals = self.locals(locals);
+    let mut appended = vec![Transform::default(); self.nodes.len()];
+    let mut world = vec![math::IDENTITY; self.nodes.len()];
+
+    for (step, &bone) in self.order.iter().enumerate() {
+      self.update(bone, &locals, &mut appended, &mut world);
+
+      if let Some(chain) = &self.nodes[bone].ik {
+        ik::solve(self, bone, chain, &mut locals, &mut appended, &mut world);
+        self.refresh(&self.order[..step], &locals, &mut appended, &mut world);
+      }
+    }
+
+    world
+  }
+
+  pub fn solve_ik(&self, bone: usize, locals: &mut [Transform], world: &mut [Mat4]) {
+    if let Some(chain) = &self.nodes[bone].ik {
+      let mut appended = vec![Transform::default(); self.nodes.len()];
+      self.refresh(&self.order, locals, &mut appended, world);
+      ik::solve(self, bone, chain, locals, &mut appended, world);
+      self.refresh(&self.order, locals, &mut appended, world);
+    }
+  }
+
+  fn append(&self, bone: usize, locals: &[Transform], appended: &mut [Transform]) -> Transform {
+    let node = &self.nodes[bone];
+    let mut transform = locals[bone];
+
+    if let Some(axis) = node.fixed_axis {
+      let [x, y, z, w] = transform.rotation;
+      let twist = math::scale(axis, math::dot([x, y, z], axis));
+      transform.rotation = math::quat_normalize([twist[0], twist[1], twist[2], w]);
+    }
+
+    if let Some(append) = &node.append {
+      // appending from a bone that appends itself only passes on what that bone appended
+      let source = if !append.local && self.nodes[append.parent].append.is_some() {
+        appended[append.parent]
+      } else {
+        locals[append.parent]
+      };
+
+      if append.rotation {
+        let rotation = math::quat_slerp(math::QUAT_IDENTITY, source.rotation, append.rate);
+        appended[bone].rotation = rotation;
+        transform.rotation = math::quat_mul(transform.rotation, rotation);
+      }
+
+      if append.translation {
+        let translation = math::scale(source.translation, append.rate);
+        appended[bone].translation = translation;
+        transform.translation = math::add(transform.translation, translation);
+      }
+    }
+
+    transform
+  }
+
+  pub fn skinning(&self, world: &[Mat4]) -> Vec<Mat4> {
+    self
+      .nodes
+      .iter()
+      .zip(world)
+      .map(|(n, w)| {
+        let inverse_bind =
+          math::mat4_from_rotation_translation(math::QUAT_IDENTITY, math::scale(n.position, -1.0));
+        math::mat4_mul(w, &inverse_bind)
+      })
+      .collect()
+  }
+
+  fn locals(&self, locals: &[Transform]) -> Vec<Transform> {
+    (0..self.nodes.len())
+      .map(|i| locals.get(i).copied().unwrap_or_default())
+      .collect()
+  }
+
+  fn refresh(
+    &self,
+    bones: &[usize],
+    locals: &[Transform],
+    appended: &mut [Transform],
+    world: &mut [Mat4],
+  ) {
+    for &bone in bones {
+      self.update(bone, locals, appended, world);
+    }
+  }
+
+  pub(crate) fn update(
+    &self,
+    bone: usize,
+    locals: &[Transform],
+    appended: &mut [Transform],
+    world: &mut [Mat4],
+  ) {
+    let transform = self.append(bone, locals, appended);
+    world[bone] = self.world_of(bone, &transform, world);
+  }
+
+  fn world_of(&self, bone: usize, transform: &Transform, world: &[Mat4]) -> Mat4 {
+    let local = self.local(bone, transform);
+    match self.nodes[bone].parent {
+      Some(p) => math::mat4_mul(&world[p], &local),
+      None => local,
+    }
+  }
+}
+
+fn index<C: Config>(len: usize, index: &C::BoneIndex) -> Result<usize> {
+  resolve(index, len).ok_or_else(|| Error::InvalidBoneIndex(raw(index)))
+}
+
+fn chain<C: Config>(nodes: &[Node], ik: &InverseKinematics<C>) -> Result<Chain> {
+  let target = index::<C>(nodes.len(), &ik.ik_bone)?;
+
+  let links = ik
+    .links
+    .iter()
+    .map(|link| {
+      Ok(Link {
+        bone: index::<C>(nodes.len(), &link.ik_bone)?,
+        limits: link
+          .limits
+          .as_ref()
+          .map(|(lower, upper)| (math::vec3(lower), math::vec3(upper))),
+      })
+    })
+    .collect::<Result<Vec<_>>>()?;
+
+  let mut path = vec![target];
+  let mut bone = target;
+  while let Some(parent) = nodes[bone].parent {
+    path.push(parent);
+    bone = parent;
+  }
+  let outermost = path
+    .iter()
+    .rposition(|b| links.iter().any(|l| l.bone == *b))
+    .unwrap_or(0);
+  path.truncate(outermost + 1);
+  path.reverse();
+
+  Ok(Chain {
+    target,
+    iterations: ik.iterations,
+    limit_angle: ik.limit_angle,
+    links,
+    path,
+  })
+}
+
+fn sort(nodes: &[Node]) -> Result<Vec<usize>> {
+  let key = |i: usize| Reverse((nodes[i].after_physics, nodes[i].transform_level, i));
+
+  let mut dependents = vec![Vec::new(); nodes.len()];
+  let mut pending = vec![0; nodes.len()];
+  let mut queue = BinaryHeap::new();
+
+  for (i, node) in nodes.iter().enumerate() {
+    let append = node.append.as_ref().map(|a| a.parent).filter(|&a| a != i);
+    for dependency in node.parent.iter().chain(append.iter()) {
+      dependents[*dependency].push(i);
+      pending[i] += 1;
+    }
+    if pending[i] == 0 {
+      queue.push(key(i));
+    }
+  }
+
+  let mut order = Vec::with_capacity(nodes.len());
+  while let Some(Reverse((_, _, i))) = queue.pop() {
+    order.push(i);
+    for &d in &dependents[i] {
+      pending[d] -= 1;
+      if pending[d] == 0 {
+        queue.push(key(d));
+      }
+    }
+  }
+
+  if order.len() < nodes.len() {
+    let mut visited = vec![false; nodes.len()];
+    for &i in &order {
+      visited[i] = true;
+    }
+    let bone = visited.iter().position(|v| !v).unwrap_or_default();
+    return Err(Error::BoneCycle(bone));
+  }
+
+  Ok(order)
+}
@@ -0,0 +1,182 @@
+use crate::math::{self, Mat4, Quat, Vec3};
+use crate::pmx::types::{raw, resolve};
+use crate::pmx::weight_deform::{Bdef4, Qdef, Sdef};
+use crate::{Config, Error, Result, Vertex, WeightDeform};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Skinned<C: Config> {
+  pub position: C::Vec3,
+  pub normal: C::Vec3,
+}
+
+// `matrices` map each bone from bind pose to posed model space,
+// that is the bone world matrix times the inverse of its bind pose world matrix.
+pub fn skin<C: Config>(vertices: &[Vertex<C>], matrices: &[Mat4]) -> Result<Vec<Skinned<C>>> {
+  vertices
+    .iter()
+    .map(|vertex| skin_vertex(vertex, matrices))
+    .collect()
+}
+
+pub fn skin_vertex<C: Config>(vertex: &Vertex<C>, matrices: &[Mat4]) -> Result<Skinned<C>> {
+  let position = math::vec3(&vertex.position);
+  let normal = math::vec3(&vertex.normal);
+
+  let (position, normal) = match &vertex.weight_deform {
+    WeightDeform::Bdef1(d) => linear::<C>(matrices, &[(&d.bone_index, 1.0)], position, normal)?,
+    WeightDeform::Bdef2(d) => linear::<C>(
+      matrices,
+      &[
+        (&d.bone_1_index, d.bone_1_weight),
+        (&d.bone_2_index, 1.0 - d.bone_1_weight),
+      ],
+      position,
+      normal,
+    )?,
+    WeightDeform::Bdef4(d) => linear::<C>(matrices, &bdef4_weights(d), position, normal)?,
+    WeightDeform::Sdef(d) => spherical(matrices, d, position, normal)?,
+    WeightDeform::Qdef(d) => dual_quaternion::<C>(matrices, &qdef_weights(d), position, normal)?,
+  };
+
+  Ok(Skinned {
+    position: position.into(),
+    normal: normal.into(),
+  })
+}
+
+fn bdef4_weights<C: Config>(d: &Bdef4<C>) -> [(&C::BoneIndex, f32); 4] {
+  [
+    (&d.bone_1_index, d.bone_1_weight),
+    (&d.bone_2_index, d.bone_2_weight),
+    (&d.bone_3_index, d.bone_3_weight),
+    (&d.bone_4_index, d.bone_4_weight),
+  ]
+}
+
+fn qdef_weights<C: Config>(d: &Qdef<C>) -> [(&C::BoneIndex, f32); 4] {
+  [
+    (&d.bone_1_index, d.bone_1_weight),
+    (&d.bone_2_index, d.bone_2_weight),
+    (&d.bone_3_index, d.bone_3_weight),
+    (&d.bone_4_index, d.bone_4_weight),
+  ]
+}
+
+fn matrix<'a, C: Config>(matrices: &'a [Mat4], index: &C::BoneIndex) -> Result<&'a Mat4> {
+  resolve(index, matrices.len())
+    .map(|i| &matrices[i])
+    .ok_or_else(|| Error::InvalidBoneIndex(raw(index)))
+}
+
+fn linear<C: Config>(
+  matrices: &[Mat4],
+  weights: &[(&C::BoneIndex, f32)],
+  position: Vec3,
+  normal: Vec3,
+) -> Result<(Vec3, Vec3)> {
+  let mut blended = [[0.0; 4]; 4];
+
+  for &(index, weight) in weights {
+    if weight == 0.0 {
+      continue;
+    }
+
+    let m = matrix::<C>(matrices, index)?;
+    for (column, source) in blended.iter_mut().zip(m) {
+      for (value, source) in column.iter_mut().zip(source) {
+        *value += source * weight;
+      }
+    }
+  }
+
+  Ok((
+    math::mat4_transform_point(&blended, position),
+    math::normalize(math::mat4_transform_vector(&blended, normal)),
+  ))
+}
+
+fn spherical<C: Config>(
+  matrices: &[Mat4],
+  d: &Sdef<C>,
+  position: Vec3,
+  normal: Vec3,
+) -> Result<(Vec3, Vec3)> {
+  let w0 = d.bone_1_weight;
+  let w1 = 1.0 - w0;
+  let m0 = matrix::<C>(matrices, &d.bone_1_index)?;
+  let m1 = matrix::<C>(matrices, &d.bone_2_index)?;
+
+  let c = math::vec3(&d.c);
+  let r0 = math::vec3(&d.r0);
+  let r1 = math::vec3(&d.r1);
+
+  let rw = math::add(math::scale(r0, w0), math::scale(r1, w1));
+  let r0 = math::sub(math::add(c, r0), rw);
+  let r1 = math::sub(math::add(c, r1), rw);
+  let cr0 = math::scale(math::add(c, r0), 0.5);
+  let cr1 = math::scale(math::add(c, r1), 0.5);
+
+  let rotation = math::quat_slerp(math::quat_from_mat4(m0), math::quat_from_mat4(m1), w1);
+
+  let position = math::add(
+    math::quat_rotate(rotation, math::sub(position, c)),
+    math::add(
+      math::scale(math::mat4_transform_point(m0, cr0), w0),
+      math::scale(math::mat4_transform_point(m1, cr1), w1),
+    ),
+  );
+
+  Ok((position, math::quat_rotate(rotation, normal)))
+}
+
+fn dual_quaternion<C: Config>(
+  matrices: &[Mat4],
+  weights: &[(&C::BoneIndex, f32)],
+  position: Vec3,
+  normal: Vec3,
+) -> Result<(Vec3, Vec3)> {
+  let mut real: Quat = [0.0; 4];
+  let mut dual: Quat = [0.0; 4];
+  let mut pivot: Option<Quat> = None;
+
+  for &(index, weight) in weights {
+    if weight == 0.0 {
+      continue;
+    }
+
+    let m = matrix::<C>(matrices, index)?;
+    let rotation = math::quat_from_mat4(m);
+    let [x, y, z] = math::mat4_translation(m);
+    let translation = math::quat_mul([x, y, z, 0.0], rotation);
+
+    let pivot = *pivot.get_or_insert(rotation);
+    let weight = if math::quat_dot(pivot, rotation) < 0.0 {
+      -weight
+    } else {
+      weight
+    };
+
+    for i in 0..4 {
+      real[i] += rotation[i] * weight;
+      dual[i] += translation[i] * 0.5 * weight;
+    }
+  }
+
+  let length = math::quat_dot(real, real).sqrt();
+  if length == 0.0 {
+    return Ok((position, normal));
+  }
+
+  for i in 0..4 {
+    real[i] /= length;
+    dual[i] /= length;
+  }
+
+  let [x, y, z, _] = math::quat_mul(dual, math::quat_conjugate(real));
+  let translation = [x * 2.0, y * 2.0, z * 2.0];
+
+  Ok((
+    math::add(math::quat_rotate(real, position), translation),
+    math::quat_rotate(real, normal),
+  ))
+}
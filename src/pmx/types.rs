@@ -1,11 +1,15 @@
 use arrayvec::ArrayVec;
 
 use crate::Error;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::convert::{TryFrom, TryInto};
 use std::fmt::{Display, Formatter};
-use std::{convert::TryFrom, fmt::Debug, iter::FromIterator};
+use std::{fmt::Debug, iter::FromIterator};
 
 #[derive(Copy, Clone, PartialEq, Eq, Debug, Hash)]
 #[repr(u8)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum TextEncoding {
   UTF16LE = 0,
   UTF8 = 1,
@@ -34,12 +38,33 @@ impl TryFrom<u8> for TextEncoding {
 
 #[derive(Copy, Clone, PartialEq, Eq, Debug, Hash)]
 #[repr(u8)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum IndexSize {
   I8 = 1,
   I16 = 2,
   I32 = 4,
 }
 
+impl IndexSize {
+  // vertex indices are unsigned, so a byte covers 256 vertices
+  pub fn for_vertices(count: usize) -> IndexSize {
+    match count {
+      0..=0x100 => IndexSize::I8,
+      0x101..=0x1_0000 => IndexSize::I16,
+      _ => IndexSize::I32,
+    }
+  }
+
+  // the other indices are signed and keep -1 for none
+  pub fn for_elements(count: usize) -> IndexSize {
+    match count {
+      0..=0x80 => IndexSize::I8,
+      0x81..=0x8000 => IndexSize::I16,
+      _ => IndexSize::I32,
+    }
+  }
+}
+
 impl Display for IndexSize {
   fn fmt(&self, f: &mut Formatter) -> Result<(), std::fmt::Error> {
     match self {
@@ -63,11 +88,20 @@ impl TryFrom<u8> for IndexSize {
   }
 }
 
-pub trait Index: TryFrom<i8> + TryFrom<i16> + TryFrom<i32> + Clone + Debug + Eq {}
-impl<I: TryFrom<i8> + TryFrom<i16> + TryFrom<i32> + Clone + Debug + Eq> Index for I {}
+pub trait Index:
+  TryFrom<i8> + TryFrom<i16> + TryFrom<i32> + TryInto<i64> + Clone + Debug + Eq
+{
+}
+impl<I: TryFrom<i8> + TryFrom<i16> + TryFrom<i32> + TryInto<i64> + Clone + Debug + Eq> Index for I {}
 
-pub trait VertexIndex: TryFrom<u8> + TryFrom<u16> + TryFrom<i32> + Clone + Debug + Eq {}
-impl<I: TryFrom<u8> + TryFrom<u16> + TryFrom<i32> + Clone + Debug + Eq> VertexIndex for I {}
+pub trait VertexIndex:
+  TryFrom<u8> + TryFrom<u16> + TryFrom<i32> + TryInto<i64> + Clone + Debug + Eq
+{
+}
+impl<I: TryFrom<u8> + TryFrom<u16> + TryFrom<i32> + TryInto<i64> + Clone + Debug + Eq> VertexIndex
+  for I
+{
+}
 
 pub trait Config {
   type VertexIndex: VertexIndex;
@@ -77,10 +111,10 @@ pub trait Config {
   type MorphIndex: Index;
   type RigidbodyIndex: Index;
 
-  type Vec2: From<[f32; 2]> + Clone + Debug + PartialEq;
-  type Vec3: From<[f32; 3]> + Clone + Debug + PartialEq;
-  type Vec4: From<[f32; 4]> + Clone + Debug + PartialEq;
-  type AdditionalVec4s: FromIterator<Self::Vec4> + Clone + Debug + PartialEq;
+  type Vec2: From<[f32; 2]> + AsRef<[f32]> + Clone + Debug + PartialEq;
+  type Vec3: From<[f32; 3]> + AsRef<[f32]> + Clone + Debug + PartialEq;
+  type Vec4: From<[f32; 4]> + AsRef<[f32]> + Clone + Debug + PartialEq;
+  type AdditionalVec4s: FromIterator<Self::Vec4> + AsRef<[Self::Vec4]> + Clone + Debug + PartialEq;
 }
 
 pub struct DefaultConfig;
@@ -113,3 +147,118 @@ impl Config for DefaultConfig {
   #[cfg(not(feature = "arrayvec"))]
   type AdditionalVec4s = Vec<Self::Vec4>;
 }
+
+// an index that decodes -1 as none instead of keeping the sentinel
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(transparent))]
+pub struct OptionalIndex(pub Option<usize>);
+
+impl OptionalIndex {
+  pub fn none() -> OptionalIndex {
+    OptionalIndex(None)
+  }
+
+  pub fn get(self) -> Option<usize> {
+    self.0
+  }
+}
+
+impl From<Option<usize>> for OptionalIndex {
+  fn from(value: Option<usize>) -> Self {
+    OptionalIndex(value)
+  }
+}
+
+impl From<OptionalIndex> for Option<usize> {
+  fn from(value: OptionalIndex) -> Self {
+    value.0
+  }
+}
+
+impl From<OptionalIndex> for i64 {
+  fn from(value: OptionalIndex) -> Self {
+    value.0.map_or(-1, |i| i as i64)
+  }
+}
+
+impl TryFrom<i64> for OptionalIndex {
+  type Error = Error;
+
+  fn try_from(value: i64) -> Result<Self, Self::Error> {
+    match value {
+      -1 => Ok(OptionalIndex(None)),
+      _ => usize::try_from(value)
+        .map(|i| OptionalIndex(Some(i)))
+        .map_err(|_| Error::IndexOverflow(value)),
+    }
+  }
+}
+
+impl TryFrom<i8> for OptionalIndex {
+  type Error = Error;
+
+  fn try_from(value: i8) -> Result<Self, Self::Error> {
+    OptionalIndex::try_from(i64::from(value))
+  }
+}
+
+impl TryFrom<i16> for OptionalIndex {
+  type Error = Error;
+
+  fn try_from(value: i16) -> Result<Self, Self::Error> {
+    OptionalIndex::try_from(i64::from(value))
+  }
+}
+
+impl TryFrom<i32> for OptionalIndex {
+  type Error = Error;
+
+  fn try_from(value: i32) -> Result<Self, Self::Error> {
+    OptionalIndex::try_from(i64::from(value))
+  }
+}
+
+impl Display for OptionalIndex {
+  fn fmt(&self, f: &mut Formatter) -> Result<(), std::fmt::Error> {
+    match self.0 {
+      Some(i) => write!(f, "{}", i),
+      None => write!(f, "none"),
+    }
+  }
+}
+
+// like the default config, but -1 decodes as none; where the format has no none, such as joint bodies
+// or IK links, it is reported by `check_references` and `validate` instead of failing the read
+pub struct OptionalConfig;
+
+impl Config for OptionalConfig {
+  type VertexIndex = i32;
+  type TextureIndex = OptionalIndex;
+  type MaterialIndex = OptionalIndex;
+  type BoneIndex = OptionalIndex;
+  type MorphIndex = OptionalIndex;
+  type RigidbodyIndex = OptionalIndex;
+
+  type Vec2 = <DefaultConfig as Config>::Vec2;
+  type Vec3 = <DefaultConfig as Config>::Vec3;
+  type Vec4 = <DefaultConfig as Config>::Vec4;
+  type AdditionalVec4s = <DefaultConfig as Config>::AdditionalVec4s;
+}
+
+// the value of an index for error messages, where anything past i64 is as out of range as i64::MAX
+pub(crate) fn raw<I: TryInto<i64> + Clone>(index: &I) -> i64 {
+  index.clone().try_into().unwrap_or(i64::MAX)
+}
+
+// the element an index points at in a section of `len`, or none for -1 and anything out of range
+pub(crate) fn resolve<I: TryInto<i64> + Clone>(index: &I, len: usize) -> Option<usize> {
+  usize::try_from(raw(index)).ok().filter(|&i| i < len)
+}
+
+// the position of a vertex reference, for exporters that cannot leave a broken one out
+pub(crate) fn vertex_index<I: TryInto<i64> + Clone>(
+  index: &I,
+  vertices: usize,
+) -> crate::Result<usize> {
+  resolve(index, vertices).ok_or_else(|| Error::InvalidVertexIndex(raw(index)))
+}
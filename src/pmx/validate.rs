@@ -0,0 +1,391 @@
+use crate::{
+  pmx::bone::BoneFlags, pmx::error::Section, pmx::morph::Offsets, pmx::types::resolve, Config,
+  Model, WeightDeform,
+};
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Severity {
+  // the model loads but tools may treat it differently than the author expected
+  Warning,
+  // writing or evaluating the model fails or reads out of bounds
+  Error,
+}
+
+impl Display for Severity {
+  fn fmt(&self, f: &mut Formatter) -> Result<(), std::fmt::Error> {
+    match self {
+      Severity::Warning => write!(f, "warning"),
+      Severity::Error => write!(f, "error"),
+    }
+  }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Problem {
+  IndexOutOfRange(Section, i64),
+  IndexSizeTooSmall(Section, usize),
+  WrongAdditionalVec4Count(usize),
+  MissingAdditionalUV(u8),
+  InvalidSurfaceCount(i32),
+  SurfaceCountMismatch(i64, usize),
+  ParentCycle,
+  AppendCycle,
+  MorphCycle,
+  DegenerateSurface,
+  WeightSum(f32),
+  DuplicateName(usize),
+  JointToItself,
+}
+
+impl Problem {
+  pub fn severity(&self) -> Severity {
+    match self {
+      Problem::DegenerateSurface
+      | Problem::WeightSum(_)
+      | Problem::DuplicateName(_)
+      | Problem::JointToItself => Severity::Warning,
+      _ => Severity::Error,
+    }
+  }
+}
+
+impl Display for Problem {
+  fn fmt(&self, f: &mut Formatter) -> Result<(), std::fmt::Error> {
+    match self {
+      Problem::IndexOutOfRange(target, index) => {
+        write!(f, "refers to {} {} out of range", target, index)
+      }
+      Problem::IndexSizeTooSmall(section, count) => {
+        write!(
+          f,
+          "{} index size cannot address {} elements",
+          section, count
+        )
+      }
+      Problem::WrongAdditionalVec4Count(count) => {
+        write!(f, "has {} additional vec4s", count)
+      }
+      Problem::MissingAdditionalUV(uv) => {
+        write!(f, "offsets additional uv {} the model does not have", uv)
+      }
+      Problem::InvalidSurfaceCount(count) => {
+        write!(f, "has surface count {} not a multiple of 3", count)
+      }
+      Problem::SurfaceCountMismatch(sum, count) => write!(
+        f,
+        "surface counts sum to {} but the model has {} surface indices",
+        sum, count
+      ),
+      Problem::ParentCycle => write!(f, "cannot be ordered because of a parent cycle"),
+      Problem::AppendCycle => write!(f, "cannot be ordered because of an append parent cycle"),
+      Problem::MorphCycle => write!(f, "is part of a group cycle"),
+      Problem::DegenerateSurface => write!(f, "repeats a vertex"),
+      Problem::WeightSum(sum) => write!(f, "has weights summing to {}", sum),
+      Problem::DuplicateName(first) => write!(f, "has the same name as #{}", first),
+      Problem::JointToItself => write!(f, "joins a rigid body to itself"),
+    }
+  }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Diagnostic {
+  pub severity: Severity,
+  pub section: Section,
+  // none when the problem is with the section as a whole
+  pub element: Option<usize>,
+  pub problem: Problem,
+}
+
+impl Display for Diagnostic {
+  fn fmt(&self, f: &mut Formatter) -> Result<(), std::fmt::Error> {
+    match self.element {
+      Some(element) => write!(f, "{}: {} #{} ", self.severity, self.section, element)?,
+      None => write!(f, "{}: {} section ", self.severity, self.section)?,
+    }
+    write!(f, "{}", self.problem)
+  }
+}
+
+struct Diagnostics(Vec<Diagnostic>);
+
+impl Diagnostics {
+  fn push(&mut self, section: Section, element: Option<usize>, problem: Problem) {
+    self.0.push(Diagnostic {
+      severity: problem.severity(),
+      section,
+      element,
+      problem,
+    });
+  }
+
+  fn duplicate_names<'a>(&mut self, section: Section, names: impl Iterator<Item = &'a String>) {
+    let mut first = HashMap::new();
+    for (i, name) in names.enumerate() {
+      if let Some(&j) = first.get(name) {
+        self.push(section, Some(i), Problem::DuplicateName(j));
+      } else {
+        first.insert(name, i);
+      }
+    }
+  }
+}
+
+// every problem found in the model, so a broken file can be reported in full instead of failing
+// on whichever section is evaluated first
+pub fn validate<C: Config>(model: &Model<C>) -> Vec<Diagnostic> {
+  let mut diagnostics = Diagnostics(Vec::new());
+
+  for (section, element, target, index) in model.broken_references() {
+    diagnostics.push(
+      section,
+      Some(element),
+      Problem::IndexOutOfRange(target, index),
+    );
+  }
+
+  let settings = &model.settings;
+  let minimal = model.minimal_settings();
+  let sizes = [
+    (
+      Section::Vertices,
+      settings.vertex_index_size,
+      minimal.vertex_index_size,
+      model.vertices.len(),
+    ),
+    (
+      Section::Textures,
+      settings.texture_index_size,
+      minimal.texture_index_size,
+      model.textures.len(),
+    ),
+    (
+      Section::Materials,
+      settings.material_index_size,
+      minimal.material_index_size,
+      model.materials.len(),
+    ),
+    (
+      Section::Bones,
+      settings.bone_index_size,
+      minimal.bone_index_size,
+      model.bones.len(),
+    ),
+    (
+      Section::Morphs,
+      settings.morph_index_size,
+      minimal.morph_index_size,
+      model.morphs.len(),
+    ),
+    (
+      Section::RigidBodies,
+      settings.rigidbody_index_size,
+      minimal.rigidbody_index_size,
+      model.rigid_bodies.len(),
+    ),
+  ];
+  for &(section, size, needed, count) in &sizes {
+    if (size as u8) < (needed as u8) {
+      diagnostics.push(
+        Section::Header,
+        None,
+        Problem::IndexSizeTooSmall(section, count),
+      );
+    }
+  }
+
+  let additional = usize::from(settings.additional_vec4_count);
+  for (i, vertex) in model.vertices.iter().enumerate() {
+    let count = vertex.additional.as_ref().len();
+    if count != additional {
+      diagnostics.push(
+        Section::Vertices,
+        Some(i),
+        Problem::WrongAdditionalVec4Count(count),
+      );
+    }
+
+    let sum = match &vertex.weight_deform {
+      WeightDeform::Bdef4(d) => {
+        d.bone_1_weight + d.bone_2_weight + d.bone_3_weight + d.bone_4_weight
+      }
+      WeightDeform::Qdef(d) => {
+        d.bone_1_weight + d.bone_2_weight + d.bone_3_weight + d.bone_4_weight
+      }
+      _ => 1.0,
+    };
+    if (sum - 1.0).abs() > 1e-3 {
+      diagnostics.push(Section::Vertices, Some(i), Problem::WeightSum(sum));
+    }
+  }
+
+  for (i, surface) in model.surfaces.iter().enumerate() {
+    let [a, b, c] = surface;
+    if a == b || b == c || c == a {
+      diagnostics.push(Section::Surfaces, Some(i), Problem::DegenerateSurface);
+    }
+  }
+
+  // materials draw consecutive runs of surface indices, which have to cover them all exactly
+  let mut sum = 0;
+  for (i, material) in model.materials.iter().enumerate() {
+    if material.surface_count < 0 || material.surface_count % 3 != 0 {
+      diagnostics.push(
+        Section::Materials,
+        Some(i),
+        Problem::InvalidSurfaceCount(material.surface_count),
+      );
+    }
+    sum += i64::from(material.surface_count);
+  }
+  if sum != model.surfaces.len() as i64 * 3 {
+    diagnostics.push(
+      Section::Materials,
+      None,
+      Problem::SurfaceCountMismatch(sum, model.surfaces.len() * 3),
+    );
+  }
+
+  diagnostics.duplicate_names(Section::Bones, model.bones.iter().map(|b| &b.local_name));
+  diagnostics.duplicate_names(Section::Morphs, model.morphs.iter().map(|m| &m.local_name));
+
+  for (bone, problem) in bone_cycles(model) {
+    diagnostics.push(Section::Bones, Some(bone), problem);
+  }
+
+  for morph in morph_cycles(model) {
+    diagnostics.push(Section::Morphs, Some(morph), Problem::MorphCycle);
+  }
+
+  for (i, morph) in model.morphs.iter().enumerate() {
+    let uv = match &morph.offsets {
+      Offsets::AdditionalUV1(_) => 1,
+      Offsets::AdditionalUV2(_) => 2,
+      Offsets::AdditionalUV3(_) => 3,
+      Offsets::AdditionalUV4(_) => 4,
+      _ => 0,
+    };
+    if uv > settings.additional_vec4_count {
+      diagnostics.push(Section::Morphs, Some(i), Problem::MissingAdditionalUV(uv));
+    }
+  }
+
+  for (i, joint) in model.joints.iter().enumerate() {
+    if joint.rigid_body_a == joint.rigid_body_b {
+      diagnostics.push(Section::Joints, Some(i), Problem::JointToItself);
+    }
+  }
+
+  diagnostics.0
+}
+
+// the bones that can never be ordered after what they depend on, whether they are on a cycle or
+// below one, told apart by whether parents alone already get stuck
+fn bone_cycles<C: Config>(model: &Model<C>) -> Vec<(usize, Problem)> {
+  let len = model.bones.len();
+  let parents: Vec<Vec<usize>> = model
+    .bones
+    .iter()
+    .map(|bone| resolve(&bone.parent, len).into_iter().collect())
+    .collect();
+  // appends are only followed when a flag turns them on, the same as the skeleton does
+  let combined: Vec<Vec<usize>> = model
+    .bones
+    .iter()
+    .enumerate()
+    .map(|(i, bone)| {
+      let flags = bone.bone_flags;
+      let enabled =
+        flags.contains(BoneFlags::AddRotation) || flags.contains(BoneFlags::AddMovement);
+      let mut dependencies = parents[i].clone();
+      if let Some(additional) = bone.additional.as_ref().filter(|_| enabled) {
+        dependencies.extend(resolve(&additional.parent, len).filter(|&a| a != i));
+      }
+      dependencies
+    })
+    .collect();
+
+  let by_parent = unsorted(&parents);
+  let by_append = unsorted(&combined);
+  (0..len)
+    .filter_map(|i| {
+      if by_parent[i] {
+        Some((i, Problem::ParentCycle))
+      } else if by_append[i] {
+        Some((i, Problem::AppendCycle))
+      } else {
+        None
+      }
+    })
+    .collect()
+}
+
+// which nodes a topological sort leaves behind
+fn unsorted(dependencies: &[Vec<usize>]) -> Vec<bool> {
+  let mut dependents = vec![Vec::new(); dependencies.len()];
+  let mut pending = vec![0; dependencies.len()];
+  let mut queue = Vec::new();
+  for (i, list) in dependencies.iter().enumerate() {
+    for &dependency in list {
+      dependents[dependency].push(i);
+      pending[i] += 1;
+    }
+    if pending[i] == 0 {
+      queue.push(i);
+    }
+  }
+
+  while let Some(i) = queue.pop() {
+    for &d in &dependents[i] {
+      pending[d] -= 1;
+      if pending[d] == 0 {
+        queue.push(d);
+      }
+    }
+  }
+  pending.into_iter().map(|p| p > 0).collect()
+}
+
+// the morphs a group or flip morph reaches itself through, one per cycle
+fn morph_cycles<C: Config>(model: &Model<C>) -> Vec<usize> {
+  let children = model
+    .morphs
+    .iter()
+    .map(|morph| match &morph.offsets {
+      Offsets::Group(offsets) | Offsets::Flip(offsets) => offsets
+        .iter()
+        .filter_map(|o| resolve(&o.morph, model.morphs.len()))
+        .collect(),
+      _ => Vec::new(),
+    })
+    .collect::<Vec<Vec<usize>>>();
+
+  // 0 unvisited, 1 on the current path, 2 done
+  let mut state = vec![0u8; children.len()];
+  let mut cycles = Vec::new();
+  for root in 0..children.len() {
+    if state[root] != 0 {
+      continue;
+    }
+    state[root] = 1;
+    let mut stack = vec![(root, 0)];
+    while let Some((morph, next)) = stack.pop() {
+      match children[morph].get(next) {
+        Some(&child) => {
+          stack.push((morph, next + 1));
+          match state[child] {
+            0 => {
+              state[child] = 1;
+              stack.push((child, 0));
+            }
+            1 => cycles.push(child),
+            _ => {}
+          }
+        }
+        None => state[morph] = 2,
+      }
+    }
+  }
+  cycles
+}
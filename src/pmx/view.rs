@@ -0,0 +1,239 @@
+use crate::pmx::error::Section;
+use crate::reader::{Limits, SectionIndex, VertexReader};
+use crate::{Config, Error, IndexSize, Result, Settings, TextEncoding, Vertex};
+use byteorder::{ByteOrder, LE};
+use encoding::all::UTF_16LE;
+use encoding::{DecoderTrap, Encoding};
+use std::borrow::Cow;
+use std::io::Cursor;
+
+// a model borrowed from a byte slice, like a memory map, only decoding what is asked for
+pub struct ModelView<'a> {
+  pub version: f32,
+  pub settings: Settings,
+  pub model_local_name: Cow<'a, str>,
+  pub model_universal_name: Cow<'a, str>,
+  pub local_comments: Cow<'a, str>,
+  pub universal_comments: Cow<'a, str>,
+  pub surfaces: Surfaces<'a>,
+  pub textures: Vec<Cow<'a, str>>,
+  index: SectionIndex,
+  data: &'a [u8],
+}
+
+impl<'a> ModelView<'a> {
+  pub fn parse(data: &'a [u8]) -> Result<ModelView<'a>> {
+    ModelView::parse_with_limits(data, Limits::default())
+  }
+
+  pub fn parse_with_limits(data: &'a [u8], limits: Limits) -> Result<ModelView<'a>> {
+    let index = SectionIndex::scan_with_limits(Cursor::new(data), limits)?;
+    let encoding = index.settings.text_encoding;
+
+    // the four header texts follow the fixed part and the globals
+    let mut offset = 9 + data.get(8).copied().unwrap_or_default() as usize;
+    let mut header_text = || {
+      let (value, next) = text(data, offset, encoding)?;
+      offset = next;
+      Ok::<_, Error>(value)
+    };
+    let model_local_name = header_text()?;
+    let model_universal_name = header_text()?;
+    let local_comments = header_text()?;
+    let universal_comments = header_text()?;
+
+    let textures = (0..index.count(Section::Textures))
+      .filter_map(|i| index.element_offset(Section::Textures, i))
+      .map(|offset| text(data, offset as usize, encoding).map(|(value, _)| value))
+      .collect::<Result<_>>()?;
+
+    Ok(ModelView {
+      version: index.version,
+      settings: index.settings,
+      model_local_name,
+      model_universal_name,
+      local_comments,
+      universal_comments,
+      surfaces: Surfaces {
+        size: index.settings.vertex_index_size,
+        bytes: slice(
+          data,
+          index.offset(Section::Surfaces) as usize,
+          index.end(Section::Surfaces) as usize,
+        )?,
+      },
+      textures,
+      index,
+      data,
+    })
+  }
+
+  pub fn vertices(&self) -> Vertices<'_> {
+    Vertices {
+      index: &self.index,
+      data: self.data,
+    }
+  }
+
+  pub fn index(&self) -> &SectionIndex {
+    &self.index
+  }
+
+  pub fn data(&self) -> &'a [u8] {
+    self.data
+  }
+
+  // local and universal name of a material, bone, morph, display frame, rigid body or joint
+  pub fn names(&self, section: Section, element: usize) -> Result<(Cow<'a, str>, Cow<'a, str>)> {
+    let offset = match section {
+      Section::Header | Section::Vertices | Section::Surfaces | Section::Textures => None,
+      _ => self.index.element_offset(section, element),
+    }
+    .ok_or(Error::InvalidElementIndex(section, element))?;
+
+    let encoding = self.settings.text_encoding;
+    let (local, next) = text(self.data, offset as usize, encoding)?;
+    let (universal, _) = text(self.data, next, encoding)?;
+    Ok((local, universal))
+  }
+}
+
+pub struct Vertices<'a> {
+  index: &'a SectionIndex,
+  data: &'a [u8],
+}
+
+impl<'a> Vertices<'a> {
+  pub fn len(&self) -> usize {
+    self.index.count(Section::Vertices)
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.len() == 0
+  }
+
+  pub fn reader(&self) -> Result<VertexReader<Cursor<&'a [u8]>>> {
+    self.index.vertices(Cursor::new(self.data), 0)
+  }
+
+  pub fn get<C: Config>(&self, vertex: usize) -> Result<Vertex<C>> {
+    self
+      .index
+      .vertices(Cursor::new(self.data), vertex)?
+      .next::<C>()?
+      .ok_or(Error::InvalidElementIndex(Section::Vertices, vertex))
+  }
+
+  pub fn positions(&self) -> Vec<[f32; 3]> {
+    self.attribute(0)
+  }
+
+  pub fn normals(&self) -> Vec<[f32; 3]> {
+    self.attribute(12)
+  }
+
+  pub fn uvs(&self) -> Vec<[f32; 2]> {
+    self.attribute(24)
+  }
+
+  pub fn additional(&self, slot: usize) -> Result<Vec<[f32; 4]>> {
+    if slot >= self.index.settings.additional_vec4_count.into() {
+      return Err(Error::WrongAdditionalVec4Count(slot + 1));
+    }
+    Ok(self.attribute(32 + 16 * slot))
+  }
+
+  // every vertex starts with the same fixed layout, so attributes are read straight from the data
+  fn attribute<const N: usize>(&self, start: usize) -> Vec<[f32; N]> {
+    (0..self.len())
+      .filter_map(|vertex| self.index.element_offset(Section::Vertices, vertex))
+      .map(|offset| {
+        let (offset, mut value) = (offset as usize, [0.0; N]);
+        if let Some(bytes) = self.data.get(offset + start..offset + start + 4 * N) {
+          LE::read_f32_into(bytes, &mut value);
+        }
+        value
+      })
+      .collect()
+  }
+}
+
+// the surface section as stored, which for 16 and 32 bit indices is directly usable as an index buffer
+#[derive(Copy, Clone, Debug)]
+pub struct Surfaces<'a> {
+  size: IndexSize,
+  bytes: &'a [u8],
+}
+
+impl<'a> Surfaces<'a> {
+  pub fn index_size(&self) -> IndexSize {
+    self.size
+  }
+
+  pub fn as_bytes(&self) -> &'a [u8] {
+    self.bytes
+  }
+
+  pub fn len(&self) -> usize {
+    self.bytes.len() / (3 * self.size as usize)
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.len() == 0
+  }
+
+  pub fn get(&self, surface: usize) -> Option<[u32; 3]> {
+    let stride = 3 * self.size as usize;
+    self
+      .bytes
+      .get(surface * stride..(surface + 1) * stride)
+      .map(|bytes| triangle(self.size, bytes))
+  }
+
+  pub fn iter(&self) -> impl Iterator<Item = [u32; 3]> + 'a {
+    let size = self.size;
+    self
+      .bytes
+      .chunks_exact(3 * size as usize)
+      .map(move |bytes| triangle(size, bytes))
+  }
+
+  pub fn to_vec(&self) -> Vec<[u32; 3]> {
+    self.iter().collect()
+  }
+}
+
+fn triangle(size: IndexSize, bytes: &[u8]) -> [u32; 3] {
+  let index = |i: usize| match size {
+    IndexSize::I8 => u32::from(bytes[i]),
+    IndexSize::I16 => u32::from(LE::read_u16(&bytes[2 * i..])),
+    IndexSize::I32 => LE::read_u32(&bytes[4 * i..]),
+  };
+  [index(0), index(1), index(2)]
+}
+
+fn slice(data: &[u8], start: usize, end: usize) -> Result<&[u8]> {
+  data
+    .get(start..end)
+    .ok_or_else(|| std::io::Error::from(std::io::ErrorKind::UnexpectedEof).into())
+}
+
+// utf8 texts are borrowed, utf16 ones have to be converted
+fn text(data: &[u8], offset: usize, encoding: TextEncoding) -> Result<(Cow<'_, str>, usize)> {
+  let size = slice(data, offset, offset + 4)?;
+  let start = offset + 4;
+  let end = start + LE::read_i32(size).max(0) as usize;
+  let bytes = slice(data, start, end)?;
+
+  let value = match encoding {
+    TextEncoding::UTF8 => std::str::from_utf8(bytes)
+      .map(Cow::Borrowed)
+      .map_err(|e| Error::DecodeText(e.to_string().into()))?,
+    TextEncoding::UTF16LE => Cow::Owned(
+      UTF_16LE
+        .decode(bytes, DecoderTrap::Strict)
+        .map_err(Error::DecodeText)?,
+    ),
+  };
+  Ok((value, end))
+}
@@ -1,15 +1,41 @@
 use crate::Config;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
+#[cfg_attr(
+  feature = "serde",
+  derive(Serialize, Deserialize),
+  serde(bound(
+    serialize = "C::BoneIndex: Serialize",
+    deserialize = "C::BoneIndex: Deserialize<'de>"
+  ))
+)]
 pub struct Bdef1<C: Config> {
   pub bone_index: C::BoneIndex,
 }
 
+#[cfg_attr(
+  feature = "serde",
+  derive(Serialize, Deserialize),
+  serde(bound(
+    serialize = "C::BoneIndex: Serialize",
+    deserialize = "C::BoneIndex: Deserialize<'de>"
+  ))
+)]
 pub struct Bdef2<C: Config> {
   pub bone_1_index: C::BoneIndex,
   pub bone_2_index: C::BoneIndex,
   pub bone_1_weight: f32,
 }
 
+#[cfg_attr(
+  feature = "serde",
+  derive(Serialize, Deserialize),
+  serde(bound(
+    serialize = "C::BoneIndex: Serialize",
+    deserialize = "C::BoneIndex: Deserialize<'de>"
+  ))
+)]
 pub struct Bdef4<C: Config> {
   pub bone_1_index: C::BoneIndex,
   pub bone_2_index: C::BoneIndex,
@@ -21,6 +47,14 @@ pub struct Bdef4<C: Config> {
   pub bone_4_weight: f32,
 }
 
+#[cfg_attr(
+  feature = "serde",
+  derive(Serialize, Deserialize),
+  serde(bound(
+    serialize = "C::BoneIndex: Serialize, C::Vec3: Serialize",
+    deserialize = "C::BoneIndex: Deserialize<'de>, C::Vec3: Deserialize<'de>"
+  ))
+)]
 pub struct Sdef<C: Config> {
   pub bone_1_index: C::BoneIndex,
   pub bone_2_index: C::BoneIndex,
@@ -30,6 +64,14 @@ pub struct Sdef<C: Config> {
   pub r1: C::Vec3,
 }
 
+#[cfg_attr(
+  feature = "serde",
+  derive(Serialize, Deserialize),
+  serde(bound(
+    serialize = "C::BoneIndex: Serialize",
+    deserialize = "C::BoneIndex: Deserialize<'de>"
+  ))
+)]
 pub struct Qdef<C: Config> {
   pub bone_1_index: C::BoneIndex,
   pub bone_2_index: C::BoneIndex,
@@ -41,6 +83,14 @@ pub struct Qdef<C: Config> {
   pub bone_4_weight: f32,
 }
 
+#[cfg_attr(
+  feature = "serde",
+  derive(Serialize, Deserialize),
+  serde(bound(
+    serialize = "C::BoneIndex: Serialize, C::Vec3: Serialize",
+    deserialize = "C::BoneIndex: Deserialize<'de>, C::Vec3: Deserialize<'de>"
+  ))
+)]
 pub enum WeightDeform<C: Config> {
   Bdef1(Bdef1<C>),
   Bdef2(Bdef2<C>),
@@ -0,0 +1,24 @@
+pub mod bone;
+pub mod display;
+pub mod header;
+pub(crate) mod helpers;
+pub mod joint;
+pub mod material;
+pub mod morph;
+pub mod rigid_body;
+pub mod soft_body;
+pub mod surface;
+pub mod texture;
+pub mod vertex;
+
+pub use bone::BoneWriter;
+pub use display::DisplayWriter;
+pub use header::HeaderWriter;
+pub use joint::JointWriter;
+pub use material::MaterialWriter;
+pub use morph::MorphWriter;
+pub use rigid_body::RigidBodyWriter;
+pub use soft_body::SoftBodyWriter;
+pub use surface::SurfaceWriter;
+pub use texture::TextureWriter;
+pub use vertex::VertexWriter;
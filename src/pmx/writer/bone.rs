@@ -0,0 +1,140 @@
+use crate::{
+  pmx::bone::*,
+  writer::{helpers::WriteHelpers, MaterialWriter},
+  Config, Error, Result, Settings,
+};
+use byteorder::{WriteBytesExt, LE};
+use std::io::Write;
+
+pub struct BoneWriter<W> {
+  pub version: f32,
+  pub settings: Settings,
+  pub count: i32,
+  pub remaining: i32,
+  pub(crate) write: W,
+  pub(crate) poison: bool,
+}
+
+impl<W: Write> BoneWriter<W> {
+  pub fn new(mut m: MaterialWriter<W>, count: i32) -> Result<BoneWriter<W>> {
+    if m.poison {
+      return Err(Error::Poisoned);
+    }
+    if m.remaining > 0 {
+      return Err(Error::SectionIncomplete(m.remaining));
+    }
+    m.write.write_i32::<LE>(count)?;
+
+    Ok(BoneWriter {
+      version: m.version,
+      settings: m.settings,
+      count,
+      remaining: count,
+      write: m.write,
+      poison: false,
+    })
+  }
+
+  pub fn write<C: Config>(&mut self, bone: &Bone<C>) -> Result<()> {
+    if self.poison {
+      return Err(Error::Poisoned);
+    }
+    let result = self.write_impl::<C>(bone);
+    if result.is_err() {
+      self.poison = true;
+    }
+    result
+  }
+
+  fn write_impl<C: Config>(&mut self, bone: &Bone<C>) -> Result<()> {
+    if self.remaining <= 0 {
+      return Err(Error::SectionOverflow(self.count));
+    }
+
+    let flags = bone.bone_flags;
+    let check = |flag: BoneFlags, present: bool| {
+      if flags.contains(flag) == present {
+        Ok(())
+      } else {
+        Err(Error::BoneFlagsMismatch(flag))
+      }
+    };
+    check(
+      BoneFlags::Connection,
+      matches!(bone.connection, Connection::Index(_)),
+    )?;
+    if flags.intersects(BoneFlags::AddRotation | BoneFlags::AddMovement)
+      != bone.additional.is_some()
+    {
+      return Err(Error::BoneFlagsMismatch(BoneFlags::AddRotation));
+    }
+    check(BoneFlags::FixedAxis, bone.fixed_axis.is_some())?;
+    check(BoneFlags::LocalAxis, bone.local_axis.is_some())?;
+    check(
+      BoneFlags::ExternalParentTransform,
+      bone.external_parent_transform.is_some(),
+    )?;
+    check(
+      BoneFlags::InverseKinematics,
+      bone.inverse_kinematics.is_some(),
+    )?;
+
+    let bone_index_size = self.settings.bone_index_size;
+
+    self
+      .write
+      .write_text(&bone.local_name, self.settings.text_encoding)?;
+    self
+      .write
+      .write_text(&bone.universal_name, self.settings.text_encoding)?;
+    self.write.write_vec(&bone.position)?;
+    self.write.write_index(bone_index_size, &bone.parent)?;
+    self.write.write_i32::<LE>(bone.transform_level)?;
+    self.write.write_u16::<LE>(flags.bits())?;
+
+    match &bone.connection {
+      Connection::Index(index) => self.write.write_index(bone_index_size, index)?,
+      Connection::Position(position) => self.write.write_vec(position)?,
+    }
+
+    if let Some(additional) = &bone.additional {
+      self
+        .write
+        .write_index(bone_index_size, &additional.parent)?;
+      self.write.write_f32::<LE>(additional.rate)?;
+    }
+
+    if let Some(fixed_axis) = &bone.fixed_axis {
+      self.write.write_vec(fixed_axis)?;
+    }
+
+    if let Some(local_axis) = &bone.local_axis {
+      self.write.write_vec(&local_axis.x)?;
+      self.write.write_vec(&local_axis.z)?;
+    }
+
+    if let Some(external_parent_transform) = bone.external_parent_transform {
+      self.write.write_i32::<LE>(external_parent_transform)?;
+    }
+
+    if let Some(ik) = &bone.inverse_kinematics {
+      self.write.write_index(bone_index_size, &ik.ik_bone)?;
+      self.write.write_u32::<LE>(ik.iterations)?;
+      self.write.write_f32::<LE>(ik.limit_angle)?;
+      self.write.write_item_count(ik.links.len())?;
+      for link in &ik.links {
+        self.write.write_index(bone_index_size, &link.ik_bone)?;
+        if let Some((low, high)) = &link.limits {
+          self.write.write_u8(1)?;
+          self.write.write_vec(low)?;
+          self.write.write_vec(high)?;
+        } else {
+          self.write.write_u8(0)?;
+        }
+      }
+    }
+
+    self.remaining -= 1;
+    Ok(())
+  }
+}
@@ -0,0 +1,83 @@
+use crate::{
+  pmx::display::*,
+  writer::{helpers::WriteHelpers, MorphWriter},
+  Config, Error, Result, Settings,
+};
+use byteorder::{WriteBytesExt, LE};
+use std::io::Write;
+
+pub struct DisplayWriter<W> {
+  pub version: f32,
+  pub settings: Settings,
+  pub count: i32,
+  pub remaining: i32,
+  pub(crate) write: W,
+  pub(crate) poison: bool,
+}
+
+impl<W: Write> DisplayWriter<W> {
+  pub fn new(mut m: MorphWriter<W>, count: i32) -> Result<DisplayWriter<W>> {
+    if m.poison {
+      return Err(Error::Poisoned);
+    }
+    if m.remaining > 0 {
+      return Err(Error::SectionIncomplete(m.remaining));
+    }
+    m.write.write_i32::<LE>(count)?;
+
+    Ok(DisplayWriter {
+      version: m.version,
+      settings: m.settings,
+      count,
+      remaining: count,
+      write: m.write,
+      poison: false,
+    })
+  }
+
+  pub fn write<C: Config>(&mut self, frame: &DisplayFrame<C>) -> Result<()> {
+    if self.poison {
+      return Err(Error::Poisoned);
+    }
+    let result = self.write_impl::<C>(frame);
+    if result.is_err() {
+      self.poison = true;
+    }
+    result
+  }
+
+  fn write_impl<C: Config>(&mut self, frame: &DisplayFrame<C>) -> Result<()> {
+    if self.remaining <= 0 {
+      return Err(Error::SectionOverflow(self.count));
+    }
+
+    self
+      .write
+      .write_text(&frame.local_name, self.settings.text_encoding)?;
+    self
+      .write
+      .write_text(&frame.universal_name, self.settings.text_encoding)?;
+    self.write.write_u8(frame.special_flag as u8)?;
+    self.write.write_item_count(frame.frames.len())?;
+
+    for frame in &frame.frames {
+      match frame {
+        Frame::Bone(index) => {
+          self.write.write_u8(0)?;
+          self
+            .write
+            .write_index(self.settings.bone_index_size, index)?;
+        }
+        Frame::Morph(index) => {
+          self.write.write_u8(1)?;
+          self
+            .write
+            .write_index(self.settings.morph_index_size, index)?;
+        }
+      }
+    }
+
+    self.remaining -= 1;
+    Ok(())
+  }
+}
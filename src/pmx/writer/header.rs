@@ -0,0 +1,47 @@
+use crate::{writer::helpers::WriteHelpers, Result, Settings};
+use byteorder::{WriteBytesExt, LE};
+use std::io::Write;
+
+pub struct HeaderWriter<W> {
+  pub version: f32,
+  pub settings: Settings,
+  pub model_local_name: String,
+  pub model_universal_name: String,
+  pub local_comments: String,
+  pub universal_comments: String,
+  pub(crate) write: W,
+}
+
+impl<W: Write> HeaderWriter<W> {
+  pub fn new(write: W, settings: Settings) -> HeaderWriter<W> {
+    HeaderWriter {
+      version: 2.0,
+      settings,
+      model_local_name: String::new(),
+      model_universal_name: String::new(),
+      local_comments: String::new(),
+      universal_comments: String::new(),
+      write,
+    }
+  }
+
+  pub(crate) fn finish(mut self) -> Result<(f32, Settings, W)> {
+    self.write.write_all(&[0x50, 0x4D, 0x58, 0x20])?;
+    self.write.write_f32::<LE>(self.version)?;
+
+    let settings = self.settings;
+    let globals = settings.globals();
+    self.write.write_u8(globals.len() as u8)?;
+    self.write.write_all(&globals)?;
+
+    let encoding = settings.text_encoding;
+    self.write.write_text(&self.model_local_name, encoding)?;
+    self
+      .write
+      .write_text(&self.model_universal_name, encoding)?;
+    self.write.write_text(&self.local_comments, encoding)?;
+    self.write.write_text(&self.universal_comments, encoding)?;
+
+    Ok((self.version, settings, self.write))
+  }
+}
@@ -0,0 +1,55 @@
+use crate::{pmx::types::*, Error, Result};
+use byteorder::{WriteBytesExt, LE};
+use encoding::all::{UTF_16LE, UTF_8};
+use encoding::{EncoderTrap, Encoding};
+use std::convert::{TryFrom, TryInto};
+use std::io::Write;
+
+pub(crate) trait WriteHelpers: Write {
+  fn write_text(&mut self, text: &str, encoding: TextEncoding) -> Result<()> {
+    let buf = (match encoding {
+      TextEncoding::UTF8 => UTF_8.encode(text, EncoderTrap::Strict),
+      TextEncoding::UTF16LE => UTF_16LE.encode(text, EncoderTrap::Strict),
+    })
+    .map_err(Error::EncodeText)?;
+    let size = i32::try_from(buf.len()).map_err(|_| Error::IndexOverflow(buf.len() as i64))?;
+    self.write_i32::<LE>(size)?;
+    self.write_all(&buf)?;
+    Ok(())
+  }
+
+  fn write_item_count(&mut self, count: usize) -> Result<()> {
+    let count = i32::try_from(count).map_err(|_| Error::IndexOverflow(count as i64))?;
+    self.write_i32::<LE>(count)?;
+    Ok(())
+  }
+
+  fn write_vec<V: AsRef<[f32]>>(&mut self, v: &V) -> Result<()> {
+    for c in v.as_ref() {
+      self.write_f32::<LE>(*c)?;
+    }
+    Ok(())
+  }
+
+  fn write_index<I: Index>(&mut self, size: IndexSize, index: &I) -> Result<()> {
+    let v: i64 = index.clone().try_into().unwrap_or(i64::MAX);
+    match size {
+      IndexSize::I8 => self.write_i8(v.try_into().map_err(|_| Error::IndexOverflow(v))?)?,
+      IndexSize::I16 => self.write_i16::<LE>(v.try_into().map_err(|_| Error::IndexOverflow(v))?)?,
+      IndexSize::I32 => self.write_i32::<LE>(v.try_into().map_err(|_| Error::IndexOverflow(v))?)?,
+    }
+    Ok(())
+  }
+
+  fn write_vertex_index<I: VertexIndex>(&mut self, size: IndexSize, index: &I) -> Result<()> {
+    let v: i64 = index.clone().try_into().unwrap_or(i64::MAX);
+    match size {
+      IndexSize::I8 => self.write_u8(v.try_into().map_err(|_| Error::IndexOverflow(v))?)?,
+      IndexSize::I16 => self.write_u16::<LE>(v.try_into().map_err(|_| Error::IndexOverflow(v))?)?,
+      IndexSize::I32 => self.write_i32::<LE>(v.try_into().map_err(|_| Error::IndexOverflow(v))?)?,
+    }
+    Ok(())
+  }
+}
+
+impl<W: Write> WriteHelpers for W {}
@@ -0,0 +1,94 @@
+use crate::{
+  pmx::joint::*,
+  writer::{helpers::WriteHelpers, RigidBodyWriter},
+  Config, Error, Result, Settings,
+};
+use byteorder::{WriteBytesExt, LE};
+use std::io::Write;
+
+pub struct JointWriter<W> {
+  pub version: f32,
+  pub settings: Settings,
+  pub count: i32,
+  pub remaining: i32,
+  pub(crate) write: W,
+  pub(crate) poison: bool,
+}
+
+impl<W: Write> JointWriter<W> {
+  pub fn new(mut r: RigidBodyWriter<W>, count: i32) -> Result<JointWriter<W>> {
+    if r.poison {
+      return Err(Error::Poisoned);
+    }
+    if r.remaining > 0 {
+      return Err(Error::SectionIncomplete(r.remaining));
+    }
+    r.write.write_i32::<LE>(count)?;
+
+    Ok(JointWriter {
+      version: r.version,
+      settings: r.settings,
+      count,
+      remaining: count,
+      write: r.write,
+      poison: false,
+    })
+  }
+
+  pub fn write<C: Config>(&mut self, joint: &Joint<C>) -> Result<()> {
+    if self.poison {
+      return Err(Error::Poisoned);
+    }
+    let result = self.write_impl::<C>(joint);
+    if result.is_err() {
+      self.poison = true;
+    }
+    result
+  }
+
+  fn write_impl<C: Config>(&mut self, joint: &Joint<C>) -> Result<()> {
+    if self.remaining <= 0 {
+      return Err(Error::SectionOverflow(self.count));
+    }
+
+    self
+      .write
+      .write_text(&joint.local_name, self.settings.text_encoding)?;
+    self
+      .write
+      .write_text(&joint.universal_name, self.settings.text_encoding)?;
+    self.write.write_u8(joint.joint_type as u8)?;
+    self
+      .write
+      .write_index(self.settings.rigidbody_index_size, &joint.rigid_body_a)?;
+    self
+      .write
+      .write_index(self.settings.rigidbody_index_size, &joint.rigid_body_b)?;
+    self.write.write_vec(&joint.position)?;
+    self.write.write_vec(&joint.rotation)?;
+    self.write.write_vec(&joint.position_min)?;
+    self.write.write_vec(&joint.position_max)?;
+    self.write.write_vec(&joint.rotation_min)?;
+    self.write.write_vec(&joint.rotation_max)?;
+    self.write.write_vec(&joint.position_spring)?;
+    self.write.write_vec(&joint.rotation_spring)?;
+
+    self.remaining -= 1;
+    Ok(())
+  }
+
+  // a 2.1 model without soft bodies still needs their count
+  pub fn finish(mut self) -> Result<W> {
+    if self.poison {
+      return Err(Error::Poisoned);
+    }
+    if self.remaining > 0 {
+      return Err(Error::SectionIncomplete(self.remaining));
+    }
+    if self.version >= 2.1 {
+      self.write.write_i32::<LE>(0)?;
+    }
+    self.write.flush()?;
+    Ok(self.write)
+  }
+}
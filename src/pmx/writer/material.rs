@@ -0,0 +1,89 @@
+use crate::{
+  pmx::material::*,
+  writer::{helpers::WriteHelpers, TextureWriter},
+  Config, Error, Result, Settings,
+};
+use byteorder::{WriteBytesExt, LE};
+use std::io::Write;
+
+pub struct MaterialWriter<W> {
+  pub version: f32,
+  pub settings: Settings,
+  pub count: i32,
+  pub remaining: i32,
+  pub(crate) write: W,
+  pub(crate) poison: bool,
+}
+
+impl<W: Write> MaterialWriter<W> {
+  pub fn new(mut t: TextureWriter<W>, count: i32) -> Result<MaterialWriter<W>> {
+    if t.poison {
+      return Err(Error::Poisoned);
+    }
+    if t.remaining > 0 {
+      return Err(Error::SectionIncomplete(t.remaining));
+    }
+    t.write.write_i32::<LE>(count)?;
+
+    Ok(MaterialWriter {
+      version: t.version,
+      settings: t.settings,
+      count,
+      remaining: count,
+      write: t.write,
+      poison: false,
+    })
+  }
+
+  pub fn write<C: Config>(&mut self, material: &Material<C>) -> Result<()> {
+    if self.poison {
+      return Err(Error::Poisoned);
+    }
+    let result = self.write_impl::<C>(material);
+    if result.is_err() {
+      self.poison = true;
+    }
+    result
+  }
+
+  fn write_impl<C: Config>(&mut self, material: &Material<C>) -> Result<()> {
+    if self.remaining <= 0 {
+      return Err(Error::SectionOverflow(self.count));
+    }
+
+    let encoding = self.settings.text_encoding;
+    let texture_index_size = self.settings.texture_index_size;
+
+    self.write.write_text(&material.local_name, encoding)?;
+    self.write.write_text(&material.universal_name, encoding)?;
+    self.write.write_vec(&material.diffuse_color)?;
+    self.write.write_vec(&material.specular_color)?;
+    self.write.write_f32::<LE>(material.specular_strength)?;
+    self.write.write_vec(&material.ambient_color)?;
+    self.write.write_u8(material.draw_flags.bits())?;
+    self.write.write_vec(&material.edge_color)?;
+    self.write.write_f32::<LE>(material.edge_scale)?;
+    self
+      .write
+      .write_index(texture_index_size, &material.texture_index)?;
+    self
+      .write
+      .write_index(texture_index_size, &material.environment_index)?;
+    self.write.write_u8(material.environment_blend_mode as u8)?;
+    match &material.toon {
+      Toon::Texture(index) => {
+        self.write.write_u8(0)?;
+        self.write.write_index(texture_index_size, index)?;
+      }
+      Toon::Internal(index) => {
+        self.write.write_u8(1)?;
+        self.write.write_u8(*index)?;
+      }
+    }
+    self.write.write_text(&material.metadata, encoding)?;
+    self.write.write_i32::<LE>(material.surface_count)?;
+
+    self.remaining -= 1;
+    Ok(())
+  }
+}
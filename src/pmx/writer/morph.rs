@@ -0,0 +1,186 @@
+use crate::{
+  pmx::morph::*,
+  writer::{helpers::WriteHelpers, BoneWriter},
+  Config, Error, Result, Settings,
+};
+use byteorder::{WriteBytesExt, LE};
+use std::io::Write;
+
+pub struct MorphWriter<W> {
+  pub version: f32,
+  pub settings: Settings,
+  pub count: i32,
+  pub remaining: i32,
+  pub(crate) write: W,
+  pub(crate) poison: bool,
+}
+
+impl<W: Write> MorphWriter<W> {
+  pub fn new(mut b: BoneWriter<W>, count: i32) -> Result<MorphWriter<W>> {
+    if b.poison {
+      return Err(Error::Poisoned);
+    }
+    if b.remaining > 0 {
+      return Err(Error::SectionIncomplete(b.remaining));
+    }
+    b.write.write_i32::<LE>(count)?;
+
+    Ok(MorphWriter {
+      version: b.version,
+      settings: b.settings,
+      count,
+      remaining: count,
+      write: b.write,
+      poison: false,
+    })
+  }
+
+  pub fn write<C: Config>(&mut self, morph: &Morph<C>) -> Result<()> {
+    if self.poison {
+      return Err(Error::Poisoned);
+    }
+    let result = self.write_impl::<C>(morph);
+    if result.is_err() {
+      self.poison = true;
+    }
+    result
+  }
+
+  fn write_impl<C: Config>(&mut self, morph: &Morph<C>) -> Result<()> {
+    if self.remaining <= 0 {
+      return Err(Error::SectionOverflow(self.count));
+    }
+
+    self
+      .write
+      .write_text(&morph.local_name, self.settings.text_encoding)?;
+    self
+      .write
+      .write_text(&morph.universal_name, self.settings.text_encoding)?;
+    self.write.write_u8(match morph.panel {
+      Panel::Hidden => 0,
+      Panel::Eyebrows => 1,
+      Panel::Eyes => 2,
+      Panel::Mouth => 3,
+      Panel::Other => 4,
+      Panel::Unknown(panel) => panel,
+    })?;
+
+    match &morph.offsets {
+      Offsets::Group(offsets) => self.write_morph_offsets(0, offsets)?,
+      Offsets::Vertex(offsets) => self.write_vertex_offsets(offsets)?,
+      Offsets::Bone(offsets) => self.write_bone_offsets(offsets)?,
+      Offsets::UV(offsets) => self.write_uv_offsets(3, offsets)?,
+      Offsets::AdditionalUV1(offsets) => self.write_uv_offsets(4, offsets)?,
+      Offsets::AdditionalUV2(offsets) => self.write_uv_offsets(5, offsets)?,
+      Offsets::AdditionalUV3(offsets) => self.write_uv_offsets(6, offsets)?,
+      Offsets::AdditionalUV4(offsets) => self.write_uv_offsets(7, offsets)?,
+      Offsets::Material(offsets) => self.write_material_offsets(offsets)?,
+      Offsets::Flip(offsets) => self.write_morph_offsets(9, offsets)?,
+      Offsets::Impulse(offsets) => self.write_impulse_offsets(offsets)?,
+    }
+
+    self.remaining -= 1;
+    Ok(())
+  }
+
+  fn write_morph_offsets<C: Config>(
+    &mut self,
+    morph_type: u8,
+    offsets: &[GroupOffset<C>],
+  ) -> Result<()> {
+    self.write.write_u8(morph_type)?;
+    self.write.write_item_count(offsets.len())?;
+
+    for offset in offsets {
+      self
+        .write
+        .write_index(self.settings.morph_index_size, &offset.morph)?;
+      self.write.write_f32::<LE>(offset.influence)?;
+    }
+
+    Ok(())
+  }
+
+  fn write_vertex_offsets<C: Config>(&mut self, offsets: &[VertexOffset<C>]) -> Result<()> {
+    self.write.write_u8(1)?;
+    self.write.write_item_count(offsets.len())?;
+
+    for offset in offsets {
+      self
+        .write
+        .write_vertex_index(self.settings.vertex_index_size, &offset.vertex)?;
+      self.write.write_vec(&offset.offset)?;
+    }
+
+    Ok(())
+  }
+
+  fn write_bone_offsets<C: Config>(&mut self, offsets: &[BoneOffset<C>]) -> Result<()> {
+    self.write.write_u8(2)?;
+    self.write.write_item_count(offsets.len())?;
+
+    for offset in offsets {
+      self
+        .write
+        .write_index(self.settings.bone_index_size, &offset.bone)?;
+      self.write.write_vec(&offset.translation)?;
+      self.write.write_vec(&offset.rotation)?;
+    }
+
+    Ok(())
+  }
+
+  fn write_uv_offsets<C: Config>(&mut self, morph_type: u8, offsets: &[UVOffset<C>]) -> Result<()> {
+    self.write.write_u8(morph_type)?;
+    self.write.write_item_count(offsets.len())?;
+
+    for offset in offsets {
+      self
+        .write
+        .write_vertex_index(self.settings.vertex_index_size, &offset.vertex)?;
+      self.write.write_vec(&offset.offset)?;
+    }
+
+    Ok(())
+  }
+
+  fn write_material_offsets<C: Config>(&mut self, offsets: &[MaterialOffset<C>]) -> Result<()> {
+    self.write.write_u8(8)?;
+    self.write.write_item_count(offsets.len())?;
+
+    for offset in offsets {
+      self
+        .write
+        .write_index(self.settings.material_index_size, &offset.material)?;
+      self.write.write_u8(offset.method as u8)?;
+      self.write.write_vec(&offset.diffuse_color)?;
+      self.write.write_vec(&offset.specular_color)?;
+      self.write.write_f32::<LE>(offset.specular_strength)?;
+      self.write.write_vec(&offset.ambient_color)?;
+      self.write.write_vec(&offset.edge_color)?;
+      self.write.write_f32::<LE>(offset.edge_scale)?;
+      self.write.write_vec(&offset.texture_tint)?;
+      self.write.write_vec(&offset.environment_tint)?;
+      self.write.write_vec(&offset.toon_tint)?;
+    }
+
+    Ok(())
+  }
+
+  fn write_impulse_offsets<C: Config>(&mut self, offsets: &[ImpulseOffset<C>]) -> Result<()> {
+    self.write.write_u8(10)?;
+    self.write.write_item_count(offsets.len())?;
+
+    for offset in offsets {
+      self
+        .write
+        .write_index(self.settings.rigidbody_index_size, &offset.rigid_body)?;
+      self.write.write_u8(offset.local as u8)?;
+      self.write.write_vec(&offset.velocity)?;
+      self.write.write_vec(&offset.torque)?;
+    }
+
+    Ok(())
+  }
+}
@@ -0,0 +1,79 @@
+use crate::{
+  pmx::rigid_body::*,
+  writer::{helpers::WriteHelpers, DisplayWriter},
+  Config, Error, Result, Settings,
+};
+use byteorder::{WriteBytesExt, LE};
+use std::io::Write;
+
+pub struct RigidBodyWriter<W> {
+  pub version: f32,
+  pub settings: Settings,
+  pub count: i32,
+  pub remaining: i32,
+  pub(crate) write: W,
+  pub(crate) poison: bool,
+}
+
+impl<W: Write> RigidBodyWriter<W> {
+  pub fn new(mut d: DisplayWriter<W>, count: i32) -> Result<RigidBodyWriter<W>> {
+    if d.poison {
+      return Err(Error::Poisoned);
+    }
+    if d.remaining > 0 {
+      return Err(Error::SectionIncomplete(d.remaining));
+    }
+    d.write.write_i32::<LE>(count)?;
+
+    Ok(RigidBodyWriter {
+      version: d.version,
+      settings: d.settings,
+      count,
+      remaining: count,
+      write: d.write,
+      poison: false,
+    })
+  }
+
+  pub fn write<C: Config>(&mut self, rigid_body: &RigidBody<C>) -> Result<()> {
+    if self.poison {
+      return Err(Error::Poisoned);
+    }
+    let result = self.write_impl::<C>(rigid_body);
+    if result.is_err() {
+      self.poison = true;
+    }
+    result
+  }
+
+  fn write_impl<C: Config>(&mut self, rigid_body: &RigidBody<C>) -> Result<()> {
+    if self.remaining <= 0 {
+      return Err(Error::SectionOverflow(self.count));
+    }
+
+    self
+      .write
+      .write_text(&rigid_body.local_name, self.settings.text_encoding)?;
+    self
+      .write
+      .write_text(&rigid_body.universal_name, self.settings.text_encoding)?;
+    self
+      .write
+      .write_index(self.settings.bone_index_size, &rigid_body.bone_index)?;
+    self.write.write_u8(rigid_body.group_id)?;
+    self.write.write_u16::<LE>(rigid_body.collision_mask)?;
+    self.write.write_u8(rigid_body.shape as u8)?;
+    self.write.write_vec(&rigid_body.shape_size)?;
+    self.write.write_vec(&rigid_body.shape_position)?;
+    self.write.write_vec(&rigid_body.shape_rotation)?;
+    self.write.write_f32::<LE>(rigid_body.mass)?;
+    self.write.write_f32::<LE>(rigid_body.move_attenuation)?;
+    self.write.write_f32::<LE>(rigid_body.rotation_damping)?;
+    self.write.write_f32::<LE>(rigid_body.repulsion)?;
+    self.write.write_f32::<LE>(rigid_body.fiction)?;
+    self.write.write_u8(rigid_body.physics_mode as u8)?;
+
+    self.remaining -= 1;
+    Ok(())
+  }
+}
@@ -0,0 +1,149 @@
+use crate::{
+  pmx::soft_body::*,
+  writer::{helpers::WriteHelpers, JointWriter},
+  Config, Error, Result, Settings,
+};
+use byteorder::{WriteBytesExt, LE};
+use std::io::Write;
+
+pub struct SoftBodyWriter<W> {
+  pub version: f32,
+  pub settings: Settings,
+  pub count: i32,
+  pub remaining: i32,
+  pub(crate) write: W,
+  pub(crate) poison: bool,
+}
+
+impl<W: Write> SoftBodyWriter<W> {
+  // 2.0 models have no soft body section, so only an empty one can be written for them
+  pub fn new(mut j: JointWriter<W>, count: i32) -> Result<SoftBodyWriter<W>> {
+    if j.poison {
+      return Err(Error::Poisoned);
+    }
+    if j.remaining > 0 {
+      return Err(Error::SectionIncomplete(j.remaining));
+    }
+    if j.version >= 2.1 {
+      j.write.write_i32::<LE>(count)?;
+    } else if count > 0 {
+      return Err(Error::UnsupportedVersion(j.version));
+    }
+
+    Ok(SoftBodyWriter {
+      version: j.version,
+      settings: j.settings,
+      count,
+      remaining: count,
+      write: j.write,
+      poison: false,
+    })
+  }
+
+  pub fn write<C: Config>(&mut self, soft_body: &SoftBody<C>) -> Result<()> {
+    if self.poison {
+      return Err(Error::Poisoned);
+    }
+    let result = self.write_impl::<C>(soft_body);
+    if result.is_err() {
+      self.poison = true;
+    }
+    result
+  }
+
+  fn write_impl<C: Config>(&mut self, soft_body: &SoftBody<C>) -> Result<()> {
+    if self.remaining <= 0 {
+      return Err(Error::SectionOverflow(self.count));
+    }
+
+    let settings = self.settings;
+    let write = &mut self.write;
+    write.write_text(&soft_body.local_name, settings.text_encoding)?;
+    write.write_text(&soft_body.universal_name, settings.text_encoding)?;
+    write.write_u8(soft_body.shape as u8)?;
+    write.write_index(settings.material_index_size, &soft_body.material_index)?;
+    write.write_u8(soft_body.group_id)?;
+    write.write_u16::<LE>(soft_body.collision_mask)?;
+    write.write_u8(soft_body.flags.bits())?;
+    write.write_i32::<LE>(soft_body.b_link_distance)?;
+    write.write_i32::<LE>(soft_body.cluster_count)?;
+    write.write_f32::<LE>(soft_body.total_mass)?;
+    write.write_f32::<LE>(soft_body.collision_margin)?;
+    write.write_i32::<LE>(soft_body.aero_model as i32)?;
+
+    let config = &soft_body.config;
+    for value in &[
+      config.velocity_correction,
+      config.damping,
+      config.drag,
+      config.lift,
+      config.pressure,
+      config.volume_conservation,
+      config.dynamic_friction,
+      config.pose_matching,
+      config.rigid_contact_hardness,
+      config.kinetic_contact_hardness,
+      config.soft_contact_hardness,
+      config.anchor_hardness,
+    ] {
+      write.write_f32::<LE>(*value)?;
+    }
+
+    let cluster = &soft_body.cluster;
+    for value in &[
+      cluster.soft_rigid_hardness,
+      cluster.soft_kinetic_hardness,
+      cluster.soft_soft_hardness,
+      cluster.soft_rigid_impulse_split,
+      cluster.soft_kinetic_impulse_split,
+      cluster.soft_soft_impulse_split,
+    ] {
+      write.write_f32::<LE>(*value)?;
+    }
+
+    let iterations = &soft_body.iterations;
+    for value in &[
+      iterations.velocity,
+      iterations.position,
+      iterations.drift,
+      iterations.cluster,
+    ] {
+      write.write_i32::<LE>(*value)?;
+    }
+
+    let material = &soft_body.material;
+    for value in &[
+      material.linear_stiffness,
+      material.area_stiffness,
+      material.volume_stiffness,
+    ] {
+      write.write_f32::<LE>(*value)?;
+    }
+
+    write.write_item_count(soft_body.anchors.len())?;
+    for anchor in &soft_body.anchors {
+      write.write_index(settings.rigidbody_index_size, &anchor.rigid_body)?;
+      write.write_vertex_index(settings.vertex_index_size, &anchor.vertex)?;
+      write.write_u8(anchor.near_mode as u8)?;
+    }
+
+    write.write_item_count(soft_body.pinned_vertices.len())?;
+    for vertex in &soft_body.pinned_vertices {
+      write.write_vertex_index(settings.vertex_index_size, vertex)?;
+    }
+
+    self.remaining -= 1;
+    Ok(())
+  }
+
+  pub fn finish(mut self) -> Result<W> {
+    if self.poison {
+      return Err(Error::Poisoned);
+    }
+    if self.remaining > 0 {
+      return Err(Error::SectionIncomplete(self.remaining));
+    }
+    self.write.flush()?;
+    Ok(self.write)
+  }
+}
@@ -0,0 +1,61 @@
+use crate::{
+  writer::{helpers::WriteHelpers, VertexWriter},
+  Config, Error, Result, Settings,
+};
+use byteorder::{WriteBytesExt, LE};
+use std::io::Write;
+
+pub struct SurfaceWriter<W> {
+  pub version: f32,
+  pub settings: Settings,
+  pub count: i32,
+  pub remaining: i32,
+  pub(crate) write: W,
+  pub(crate) poison: bool,
+}
+
+impl<W: Write> SurfaceWriter<W> {
+  pub fn new(mut v: VertexWriter<W>, count: i32) -> Result<SurfaceWriter<W>> {
+    if v.poison {
+      return Err(Error::Poisoned);
+    }
+    if v.remaining > 0 {
+      return Err(Error::SectionIncomplete(v.remaining));
+    }
+    v.write.write_i32::<LE>(count)?;
+
+    Ok(SurfaceWriter {
+      version: v.version,
+      settings: v.settings,
+      count,
+      remaining: count,
+      write: v.write,
+      poison: false,
+    })
+  }
+
+  pub fn write<C: Config>(&mut self, surface: &[C::VertexIndex; 3]) -> Result<()> {
+    if self.poison {
+      return Err(Error::Poisoned);
+    }
+    let result = self.write_impl::<C>(surface);
+    if result.is_err() {
+      self.poison = true;
+    }
+    result
+  }
+
+  fn write_impl<C: Config>(&mut self, surface: &[C::VertexIndex; 3]) -> Result<()> {
+    if self.remaining < 3 {
+      return Err(Error::SectionOverflow(self.count));
+    }
+
+    for index in surface {
+      self
+        .write
+        .write_vertex_index(self.settings.vertex_index_size, index)?;
+    }
+    self.remaining -= 3;
+    Ok(())
+  }
+}
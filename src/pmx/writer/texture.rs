@@ -0,0 +1,59 @@
+use crate::{
+  writer::{helpers::WriteHelpers, SurfaceWriter},
+  Error, Result, Settings,
+};
+use byteorder::{WriteBytesExt, LE};
+use std::io::Write;
+
+pub struct TextureWriter<W> {
+  pub version: f32,
+  pub settings: Settings,
+  pub count: i32,
+  pub remaining: i32,
+  pub(crate) write: W,
+  pub(crate) poison: bool,
+}
+
+impl<W: Write> TextureWriter<W> {
+  pub fn new(mut s: SurfaceWriter<W>, count: i32) -> Result<TextureWriter<W>> {
+    if s.poison {
+      return Err(Error::Poisoned);
+    }
+    if s.remaining > 0 {
+      return Err(Error::SectionIncomplete(s.remaining));
+    }
+    s.write.write_i32::<LE>(count)?;
+
+    Ok(TextureWriter {
+      version: s.version,
+      settings: s.settings,
+      count,
+      remaining: count,
+      write: s.write,
+      poison: false,
+    })
+  }
+
+  pub fn write(&mut self, texture: &str) -> Result<()> {
+    if self.poison {
+      return Err(Error::Poisoned);
+    }
+    let result = self.write_impl(texture);
+    if result.is_err() {
+      self.poison = true;
+    }
+    result
+  }
+
+  fn write_impl(&mut self, texture: &str) -> Result<()> {
+    if self.remaining <= 0 {
+      return Err(Error::SectionOverflow(self.count));
+    }
+
+    self
+      .write
+      .write_text(texture, self.settings.text_encoding)?;
+    self.remaining -= 1;
+    Ok(())
+  }
+}
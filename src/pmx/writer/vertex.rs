@@ -0,0 +1,108 @@
+use crate::{
+  pmx::weight_deform::*,
+  writer::{helpers::WriteHelpers, HeaderWriter},
+  Config, Error, Result, Settings, Vertex,
+};
+use byteorder::{WriteBytesExt, LE};
+use std::io::Write;
+
+pub struct VertexWriter<W> {
+  pub version: f32,
+  pub settings: Settings,
+  pub count: i32,
+  pub remaining: i32,
+  pub(crate) write: W,
+  pub(crate) poison: bool,
+}
+
+impl<W: Write> VertexWriter<W> {
+  pub fn new(header: HeaderWriter<W>, count: i32) -> Result<VertexWriter<W>> {
+    let (version, settings, mut write) = header.finish()?;
+    write.write_i32::<LE>(count)?;
+    Ok(VertexWriter {
+      version,
+      settings,
+      count,
+      remaining: count,
+      write,
+      poison: false,
+    })
+  }
+
+  pub fn write<C: Config>(&mut self, vertex: &Vertex<C>) -> Result<()> {
+    if self.poison {
+      return Err(Error::Poisoned);
+    }
+    let result = self.write_impl::<C>(vertex);
+    if result.is_err() {
+      self.poison = true;
+    }
+    result
+  }
+
+  fn write_impl<C: Config>(&mut self, vertex: &Vertex<C>) -> Result<()> {
+    if self.remaining <= 0 {
+      return Err(Error::SectionOverflow(self.count));
+    }
+    let additional = vertex.additional.as_ref();
+    if additional.len() != self.settings.additional_vec4_count as usize {
+      return Err(Error::WrongAdditionalVec4Count(additional.len()));
+    }
+
+    self.write.write_vec(&vertex.position)?;
+    self.write.write_vec(&vertex.normal)?;
+    self.write.write_vec(&vertex.uv)?;
+    for v in additional {
+      self.write.write_vec(v)?;
+    }
+
+    let bone_index_size = self.settings.bone_index_size;
+    match &vertex.weight_deform {
+      WeightDeform::Bdef1(w) => {
+        self.write.write_u8(0)?;
+        self.write.write_index(bone_index_size, &w.bone_index)?;
+      }
+      WeightDeform::Bdef2(w) => {
+        self.write.write_u8(1)?;
+        self.write.write_index(bone_index_size, &w.bone_1_index)?;
+        self.write.write_index(bone_index_size, &w.bone_2_index)?;
+        self.write.write_f32::<LE>(w.bone_1_weight)?;
+      }
+      WeightDeform::Bdef4(w) => {
+        self.write.write_u8(2)?;
+        self.write.write_index(bone_index_size, &w.bone_1_index)?;
+        self.write.write_index(bone_index_size, &w.bone_2_index)?;
+        self.write.write_index(bone_index_size, &w.bone_3_index)?;
+        self.write.write_index(bone_index_size, &w.bone_4_index)?;
+        self.write.write_f32::<LE>(w.bone_1_weight)?;
+        self.write.write_f32::<LE>(w.bone_2_weight)?;
+        self.write.write_f32::<LE>(w.bone_3_weight)?;
+        self.write.write_f32::<LE>(w.bone_4_weight)?;
+      }
+      WeightDeform::Sdef(w) => {
+        self.write.write_u8(3)?;
+        self.write.write_index(bone_index_size, &w.bone_1_index)?;
+        self.write.write_index(bone_index_size, &w.bone_2_index)?;
+        self.write.write_f32::<LE>(w.bone_1_weight)?;
+        self.write.write_vec(&w.c)?;
+        self.write.write_vec(&w.r0)?;
+        self.write.write_vec(&w.r1)?;
+      }
+      WeightDeform::Qdef(w) => {
+        self.write.write_u8(4)?;
+        self.write.write_index(bone_index_size, &w.bone_1_index)?;
+        self.write.write_index(bone_index_size, &w.bone_2_index)?;
+        self.write.write_index(bone_index_size, &w.bone_3_index)?;
+        self.write.write_index(bone_index_size, &w.bone_4_index)?;
+        self.write.write_f32::<LE>(w.bone_1_weight)?;
+        self.write.write_f32::<LE>(w.bone_2_weight)?;
+        self.write.write_f32::<LE>(w.bone_3_weight)?;
+        self.write.write_f32::<LE>(w.bone_4_weight)?;
+      }
+    }
+
+    self.write.write_f32::<LE>(vertex.edge_scale)?;
+    self.remaining -= 1;
+    Ok(())
+  }
+}
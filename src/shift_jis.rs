@@ -0,0 +1,41 @@
+use crate::{Error, Result};
+use encoding::all::WINDOWS_31J;
+use encoding::{DecoderTrap, EncoderTrap, Encoding};
+use std::io::{Read, Write};
+
+pub(crate) trait ReadShiftJis: Read {
+  fn read_shift_jis(&mut self, len: usize) -> Result<String> {
+    let mut buf = vec![0u8; len];
+    self.read_exact(&mut buf)?;
+    let end = buf.iter().position(|&b| b == 0).unwrap_or(len);
+    WINDOWS_31J
+      .decode(&buf[..end], DecoderTrap::Strict)
+      .or_else(|e| {
+        // names filling the whole field may be cut in the middle of a double byte character
+        if end == len {
+          WINDOWS_31J
+            .decode(&buf[..end - 1], DecoderTrap::Strict)
+            .map_err(|_| e)
+        } else {
+          Err(e)
+        }
+      })
+      .map_err(Error::DecodeText)
+  }
+}
+
+impl<R: Read> ReadShiftJis for R {}
+
+pub(crate) trait WriteShiftJis: Write {
+  fn write_shift_jis(&mut self, text: &str, len: usize) -> Result<()> {
+    let mut buf = WINDOWS_31J
+      .encode(text, EncoderTrap::Strict)
+      .map_err(Error::EncodeText)?;
+    // MMD matches names by their leading bytes, so long names are cut at the exact byte length
+    buf.resize(len, 0u8);
+    self.write_all(&buf)?;
+    Ok(())
+  }
+}
+
+impl<W: Write> WriteShiftJis for W {}
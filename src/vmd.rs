@@ -0,0 +1,16 @@
+pub mod bone;
+pub mod camera;
+pub mod ik;
+pub mod interpolation;
+pub mod light;
+pub mod morph;
+pub mod reader;
+pub mod shadow;
+pub mod writer;
+
+pub use bone::BoneKeyframe;
+pub use camera::CameraKeyframe;
+pub use ik::IkKeyframe;
+pub use light::LightKeyframe;
+pub use morph::MorphKeyframe;
+pub use shadow::ShadowKeyframe;
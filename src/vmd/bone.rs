@@ -0,0 +1,26 @@
+use crate::{vmd::interpolation::BoneInterpolation, Config};
+use std::fmt::{Display, Formatter};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BoneKeyframe<C: Config> {
+  pub bone_name: String,
+  pub frame: u32,
+  pub translation: C::Vec3,
+  pub rotation: C::Vec4,
+  pub interpolation: BoneInterpolation,
+}
+
+impl<C: Config> Display for BoneKeyframe<C>
+where
+  C::Vec3: Display,
+  C::Vec4: Display,
+{
+  fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+    write!(
+      f,
+      r"bone: {}, frame: {}, translation: {}, rotation: {}
+interpolation: {}",
+      self.bone_name, self.frame, self.translation, self.rotation, self.interpolation
+    )
+  }
+}
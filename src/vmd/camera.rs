@@ -0,0 +1,33 @@
+use crate::{vmd::interpolation::CameraInterpolation, Config};
+use std::fmt::{Display, Formatter};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CameraKeyframe<C: Config> {
+  pub frame: u32,
+  pub distance: f32,
+  pub position: C::Vec3,
+  pub rotation: C::Vec3,
+  pub interpolation: CameraInterpolation,
+  pub fov: u32,
+  pub perspective: bool,
+}
+
+impl<C: Config> Display for CameraKeyframe<C>
+where
+  C::Vec3: Display,
+{
+  fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+    write!(
+      f,
+      r"frame: {}, distance: {}, position: {}, rotation: {},
+fov: {}, perspective: {}, interpolation: {}",
+      self.frame,
+      self.distance,
+      self.position,
+      self.rotation,
+      self.fov,
+      self.perspective,
+      self.interpolation
+    )
+  }
+}
@@ -0,0 +1,38 @@
+use itertools::Itertools;
+use std::fmt::{Display, Formatter};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IkState {
+  pub bone_name: String,
+  pub enabled: bool,
+}
+
+impl Display for IkState {
+  fn fmt(&self, f: &mut Formatter) -> Result<(), std::fmt::Error> {
+    write!(
+      f,
+      "{} {}",
+      self.bone_name,
+      if self.enabled { "on" } else { "off" }
+    )
+  }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IkKeyframe {
+  pub frame: u32,
+  pub visible: bool,
+  pub ik_states: Vec<IkState>,
+}
+
+impl Display for IkKeyframe {
+  fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+    write!(
+      f,
+      "frame: {}, visible: {}, ik: {}",
+      self.frame,
+      self.visible,
+      self.ik_states.iter().map(ToString::to_string).join(", ")
+    )
+  }
+}
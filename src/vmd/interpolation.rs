@@ -0,0 +1,134 @@
+use std::fmt::{Display, Formatter};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Bezier {
+  pub x1: u8,
+  pub y1: u8,
+  pub x2: u8,
+  pub y2: u8,
+}
+
+impl Default for Bezier {
+  fn default() -> Self {
+    Bezier {
+      x1: 20,
+      y1: 20,
+      x2: 107,
+      y2: 107,
+    }
+  }
+}
+
+impl Display for Bezier {
+  fn fmt(&self, f: &mut Formatter) -> Result<(), std::fmt::Error> {
+    write!(f, "({}, {})-({}, {})", self.x1, self.y1, self.x2, self.y2)
+  }
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct BoneInterpolation {
+  pub x: Bezier,
+  pub y: Bezier,
+  pub z: Bezier,
+  pub rotation: Bezier,
+}
+
+impl BoneInterpolation {
+  pub(crate) fn from_bytes(bytes: &[u8; 64]) -> Self {
+    let curve = |i: usize| Bezier {
+      x1: bytes[i],
+      y1: bytes[i + 4],
+      x2: bytes[i + 8],
+      y2: bytes[i + 12],
+    };
+    BoneInterpolation {
+      x: curve(0),
+      y: curve(1),
+      z: curve(2),
+      rotation: curve(3),
+    }
+  }
+
+  pub(crate) fn to_bytes(self) -> [u8; 64] {
+    let curves = [self.x, self.y, self.z, self.rotation];
+    let mut row = [0u8; 16];
+    for (i, curve) in curves.iter().enumerate() {
+      row[i] = curve.x1;
+      row[i + 4] = curve.y1;
+      row[i + 8] = curve.x2;
+      row[i + 12] = curve.y2;
+    }
+
+    // MMD repeats the first row three more times, each shifted one byte to the left
+    let mut bytes = [0u8; 64];
+    for shift in 0..4 {
+      bytes[shift * 16..shift * 16 + 16 - shift].copy_from_slice(&row[shift..]);
+    }
+    bytes
+  }
+}
+
+impl Display for BoneInterpolation {
+  fn fmt(&self, f: &mut Formatter) -> Result<(), std::fmt::Error> {
+    write!(
+      f,
+      "x: {}, y: {}, z: {}, rotation: {}",
+      self.x, self.y, self.z, self.rotation
+    )
+  }
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct CameraInterpolation {
+  pub x: Bezier,
+  pub y: Bezier,
+  pub z: Bezier,
+  pub rotation: Bezier,
+  pub distance: Bezier,
+  pub fov: Bezier,
+}
+
+impl CameraInterpolation {
+  pub(crate) fn from_bytes(bytes: &[u8; 24]) -> Self {
+    let curve = |i: usize| Bezier {
+      x1: bytes[i * 4],
+      x2: bytes[i * 4 + 1],
+      y1: bytes[i * 4 + 2],
+      y2: bytes[i * 4 + 3],
+    };
+    CameraInterpolation {
+      x: curve(0),
+      y: curve(1),
+      z: curve(2),
+      rotation: curve(3),
+      distance: curve(4),
+      fov: curve(5),
+    }
+  }
+
+  pub(crate) fn to_bytes(self) -> [u8; 24] {
+    let curves = [
+      self.x,
+      self.y,
+      self.z,
+      self.rotation,
+      self.distance,
+      self.fov,
+    ];
+    let mut bytes = [0u8; 24];
+    for (i, curve) in curves.iter().enumerate() {
+      bytes[i * 4..i * 4 + 4].copy_from_slice(&[curve.x1, curve.x2, curve.y1, curve.y2]);
+    }
+    bytes
+  }
+}
+
+impl Display for CameraInterpolation {
+  fn fmt(&self, f: &mut Formatter) -> Result<(), std::fmt::Error> {
+    write!(
+      f,
+      "x: {}, y: {}, z: {}, rotation: {}, distance: {}, fov: {}",
+      self.x, self.y, self.z, self.rotation, self.distance, self.fov
+    )
+  }
+}
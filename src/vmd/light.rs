@@ -0,0 +1,22 @@
+use crate::Config;
+use std::fmt::{Display, Formatter};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct LightKeyframe<C: Config> {
+  pub frame: u32,
+  pub color: C::Vec3,
+  pub direction: C::Vec3,
+}
+
+impl<C: Config> Display for LightKeyframe<C>
+where
+  C::Vec3: Display,
+{
+  fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+    write!(
+      f,
+      "frame: {}, color: {}, direction: {}",
+      self.frame, self.color, self.direction
+    )
+  }
+}
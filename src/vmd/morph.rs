@@ -0,0 +1,18 @@
+use std::fmt::{Display, Formatter};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct MorphKeyframe {
+  pub morph_name: String,
+  pub frame: u32,
+  pub weight: f32,
+}
+
+impl Display for MorphKeyframe {
+  fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+    write!(
+      f,
+      "morph: {}, frame: {}, weight: {}",
+      self.morph_name, self.frame, self.weight
+    )
+  }
+}
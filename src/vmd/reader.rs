@@ -0,0 +1,16 @@
+pub mod bone;
+pub mod camera;
+pub mod header;
+mod helpers;
+pub mod ik;
+pub mod light;
+pub mod morph;
+pub mod shadow;
+
+pub use bone::BoneKeyframeReader;
+pub use camera::CameraKeyframeReader;
+pub use header::HeaderReader;
+pub use ik::IkKeyframeReader;
+pub use light::LightKeyframeReader;
+pub use morph::MorphKeyframeReader;
+pub use shadow::ShadowKeyframeReader;
@@ -0,0 +1,101 @@
+use crate::{
+  reader::helpers::ReadHelpers,
+  shift_jis::ReadShiftJis,
+  vmd::bone::*,
+  vmd::interpolation::BoneInterpolation,
+  vmd::reader::{helpers::VmdReadHelpers, HeaderReader},
+  Config, DefaultConfig, Error, Result,
+};
+use byteorder::{ReadBytesExt, LE};
+use std::io::Read;
+use std::marker::PhantomData;
+
+pub struct BoneKeyframeReader<R> {
+  pub count: u32,
+  pub remaining: u32,
+  pub(crate) read: R,
+  pub(crate) poison: bool,
+}
+
+impl<R: Read> BoneKeyframeReader<R> {
+  pub fn new(mut h: HeaderReader<R>) -> Result<BoneKeyframeReader<R>> {
+    let count = h.read.read_count()?;
+
+    Ok(BoneKeyframeReader {
+      count,
+      remaining: count,
+      read: h.read,
+      poison: false,
+    })
+  }
+
+  pub fn next<C: Config>(&mut self) -> Result<Option<BoneKeyframe<C>>> {
+    if self.poison {
+      return Err(Error::Poisoned);
+    }
+    let result = self.next_impl::<C>();
+    if result.is_err() {
+      self.poison = true;
+    }
+    result
+  }
+
+  fn next_impl<C: Config>(&mut self) -> Result<Option<BoneKeyframe<C>>> {
+    if self.remaining == 0 {
+      return Ok(None);
+    }
+
+    self.remaining -= 1;
+
+    let bone_name = self.read.read_shift_jis(15)?;
+    let frame = self.read.read_u32::<LE>()?;
+    let translation = self.read.read_vec3::<C>()?;
+    let rotation = self.read.read_vec4::<C>()?;
+    let mut interpolation = [0u8; 64];
+    self.read.read_exact(&mut interpolation)?;
+
+    Ok(Some(BoneKeyframe {
+      bone_name,
+      frame,
+      translation,
+      rotation,
+      interpolation: BoneInterpolation::from_bytes(&interpolation),
+    }))
+  }
+
+  pub fn iter<C>(&mut self) -> BoneKeyframeIterator<'_, R, C> {
+    BoneKeyframeIterator {
+      reader: self,
+      phantom: PhantomData,
+    }
+  }
+}
+
+pub struct BoneKeyframeIterator<'a, R, C = DefaultConfig> {
+  reader: &'a mut BoneKeyframeReader<R>,
+  phantom: PhantomData<C>,
+}
+
+impl<R: Read, C: Config> Iterator for BoneKeyframeIterator<'_, R, C> {
+  type Item = Result<BoneKeyframe<C>>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    self
+      .reader
+      .next()
+      .map_or_else(|e| Some(Err(e)), |v| v.map(Ok))
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    (
+      self.reader.remaining as usize,
+      Some(self.reader.remaining as usize),
+    )
+  }
+}
+
+impl<R: Read, C: Config> ExactSizeIterator for BoneKeyframeIterator<'_, R, C> {
+  fn len(&self) -> usize {
+    self.reader.remaining as usize
+  }
+}
@@ -0,0 +1,108 @@
+use crate::{
+  reader::helpers::ReadHelpers,
+  vmd::camera::*,
+  vmd::interpolation::CameraInterpolation,
+  vmd::reader::{helpers::VmdReadHelpers, MorphKeyframeReader},
+  Config, DefaultConfig, Error, Result,
+};
+use byteorder::{ReadBytesExt, LE};
+use std::io::Read;
+use std::marker::PhantomData;
+
+pub struct CameraKeyframeReader<R> {
+  pub count: u32,
+  pub remaining: u32,
+  pub(crate) read: R,
+  pub(crate) poison: bool,
+}
+
+impl<R: Read> CameraKeyframeReader<R> {
+  pub fn new(mut m: MorphKeyframeReader<R>) -> Result<CameraKeyframeReader<R>> {
+    if m.poison {
+      return Err(Error::Poisoned);
+    }
+    while m.remaining > 0 {
+      m.next()?;
+    }
+    let count = m.read.read_count()?;
+
+    Ok(CameraKeyframeReader {
+      count,
+      remaining: count,
+      read: m.read,
+      poison: false,
+    })
+  }
+
+  pub fn next<C: Config>(&mut self) -> Result<Option<CameraKeyframe<C>>> {
+    if self.poison {
+      return Err(Error::Poisoned);
+    }
+    let result = self.next_impl::<C>();
+    if result.is_err() {
+      self.poison = true;
+    }
+    result
+  }
+
+  fn next_impl<C: Config>(&mut self) -> Result<Option<CameraKeyframe<C>>> {
+    if self.remaining == 0 {
+      return Ok(None);
+    }
+
+    self.remaining -= 1;
+
+    let frame = self.read.read_u32::<LE>()?;
+    let distance = self.read.read_f32::<LE>()?;
+    let position = self.read.read_vec3::<C>()?;
+    let rotation = self.read.read_vec3::<C>()?;
+    let mut interpolation = [0u8; 24];
+    self.read.read_exact(&mut interpolation)?;
+
+    Ok(Some(CameraKeyframe {
+      frame,
+      distance,
+      position,
+      rotation,
+      interpolation: CameraInterpolation::from_bytes(&interpolation),
+      fov: self.read.read_u32::<LE>()?,
+      perspective: self.read.read_u8()? == 0,
+    }))
+  }
+
+  pub fn iter<C>(&mut self) -> CameraKeyframeIterator<'_, R, C> {
+    CameraKeyframeIterator {
+      reader: self,
+      phantom: PhantomData,
+    }
+  }
+}
+
+pub struct CameraKeyframeIterator<'a, R, C = DefaultConfig> {
+  reader: &'a mut CameraKeyframeReader<R>,
+  phantom: PhantomData<C>,
+}
+
+impl<R: Read, C: Config> Iterator for CameraKeyframeIterator<'_, R, C> {
+  type Item = Result<CameraKeyframe<C>>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    self
+      .reader
+      .next()
+      .map_or_else(|e| Some(Err(e)), |v| v.map(Ok))
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    (
+      self.reader.remaining as usize,
+      Some(self.reader.remaining as usize),
+    )
+  }
+}
+
+impl<R: Read, C: Config> ExactSizeIterator for CameraKeyframeIterator<'_, R, C> {
+  fn len(&self) -> usize {
+    self.reader.remaining as usize
+  }
+}
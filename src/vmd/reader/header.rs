@@ -0,0 +1,33 @@
+use crate::{shift_jis::ReadShiftJis, Error};
+use std::fmt::{Display, Formatter};
+use std::io::Read;
+
+pub struct HeaderReader<R> {
+  pub model_name: String,
+  pub(crate) read: R,
+}
+
+impl<R: Read> HeaderReader<R> {
+  pub fn new(mut read: R) -> Result<HeaderReader<R>, Error> {
+    let mut magic = [0u8; 30];
+    read.read_exact(&mut magic)?;
+    let name_length = if magic.starts_with(b"Vocaloid Motion Data 0002") {
+      20
+    } else if magic.starts_with(b"Vocaloid Motion Data file") {
+      10
+    } else {
+      return Err(Error::WrongVmdSignature(magic));
+    };
+
+    Ok(HeaderReader {
+      model_name: read.read_shift_jis(name_length)?,
+      read,
+    })
+  }
+}
+
+impl<R> Display for HeaderReader<R> {
+  fn fmt(&self, f: &mut Formatter) -> Result<(), std::fmt::Error> {
+    writeln!(f, "model name: {}", self.model_name)
+  }
+}
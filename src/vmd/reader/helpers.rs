@@ -0,0 +1,16 @@
+use crate::{Error, Result};
+use byteorder::{ReadBytesExt, LE};
+use std::io::{ErrorKind, Read};
+
+pub(crate) trait VmdReadHelpers: Read {
+  // older motions simply end after the last section they use
+  fn read_count(&mut self) -> Result<u32> {
+    match self.read_u32::<LE>() {
+      Ok(count) => Ok(count),
+      Err(e) if e.kind() == ErrorKind::UnexpectedEof => Ok(0),
+      Err(e) => Err(Error::Io(e)),
+    }
+  }
+}
+
+impl<R: Read> VmdReadHelpers for R {}
@@ -0,0 +1,102 @@
+use crate::{
+  shift_jis::ReadShiftJis,
+  vmd::ik::*,
+  vmd::reader::{helpers::VmdReadHelpers, ShadowKeyframeReader},
+  Error, Result,
+};
+use byteorder::{ReadBytesExt, LE};
+use std::io::Read;
+
+pub struct IkKeyframeReader<R> {
+  pub count: u32,
+  pub remaining: u32,
+  pub(crate) read: R,
+  pub(crate) poison: bool,
+}
+
+impl<R: Read> IkKeyframeReader<R> {
+  pub fn new(mut s: ShadowKeyframeReader<R>) -> Result<IkKeyframeReader<R>> {
+    if s.poison {
+      return Err(Error::Poisoned);
+    }
+    while s.remaining > 0 {
+      s.next()?;
+    }
+    let count = s.read.read_count()?;
+
+    Ok(IkKeyframeReader {
+      count,
+      remaining: count,
+      read: s.read,
+      poison: false,
+    })
+  }
+
+  pub fn next(&mut self) -> Result<Option<IkKeyframe>> {
+    if self.poison {
+      return Err(Error::Poisoned);
+    }
+    let result = self.next_impl();
+    if result.is_err() {
+      self.poison = true;
+    }
+    result
+  }
+
+  fn next_impl(&mut self) -> Result<Option<IkKeyframe>> {
+    if self.remaining == 0 {
+      return Ok(None);
+    }
+
+    self.remaining -= 1;
+
+    let frame = self.read.read_u32::<LE>()?;
+    let visible = self.read.read_u8()? != 0;
+    let ik_count = self.read.read_u32::<LE>()?;
+    let mut ik_states = Vec::new();
+
+    for _ in 0..ik_count {
+      ik_states.push(IkState {
+        bone_name: self.read.read_shift_jis(20)?,
+        enabled: self.read.read_u8()? != 0,
+      });
+    }
+
+    Ok(Some(IkKeyframe {
+      frame,
+      visible,
+      ik_states,
+    }))
+  }
+
+  pub fn iter(&mut self) -> IkKeyframeIterator<'_, R> {
+    IkKeyframeIterator { reader: self }
+  }
+}
+pub struct IkKeyframeIterator<'a, R> {
+  reader: &'a mut IkKeyframeReader<R>,
+}
+
+impl<R: Read> Iterator for IkKeyframeIterator<'_, R> {
+  type Item = Result<IkKeyframe>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    self
+      .reader
+      .next()
+      .map_or_else(|e| Some(Err(e)), |v| v.map(Ok))
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    (
+      self.reader.remaining as usize,
+      Some(self.reader.remaining as usize),
+    )
+  }
+}
+
+impl<R: Read> ExactSizeIterator for IkKeyframeIterator<'_, R> {
+  fn len(&self) -> usize {
+    self.reader.remaining as usize
+  }
+}
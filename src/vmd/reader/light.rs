@@ -0,0 +1,96 @@
+use crate::{
+  reader::helpers::ReadHelpers,
+  vmd::light::*,
+  vmd::reader::{helpers::VmdReadHelpers, CameraKeyframeReader},
+  Config, DefaultConfig, Error, Result,
+};
+use byteorder::{ReadBytesExt, LE};
+use std::io::Read;
+use std::marker::PhantomData;
+
+pub struct LightKeyframeReader<R> {
+  pub count: u32,
+  pub remaining: u32,
+  pub(crate) read: R,
+  pub(crate) poison: bool,
+}
+
+impl<R: Read> LightKeyframeReader<R> {
+  pub fn new(mut c: CameraKeyframeReader<R>) -> Result<LightKeyframeReader<R>> {
+    if c.poison {
+      return Err(Error::Poisoned);
+    }
+    while c.remaining > 0 {
+      c.next::<DefaultConfig>()?;
+    }
+    let count = c.read.read_count()?;
+
+    Ok(LightKeyframeReader {
+      count,
+      remaining: count,
+      read: c.read,
+      poison: false,
+    })
+  }
+
+  pub fn next<C: Config>(&mut self) -> Result<Option<LightKeyframe<C>>> {
+    if self.poison {
+      return Err(Error::Poisoned);
+    }
+    let result = self.next_impl::<C>();
+    if result.is_err() {
+      self.poison = true;
+    }
+    result
+  }
+
+  fn next_impl<C: Config>(&mut self) -> Result<Option<LightKeyframe<C>>> {
+    if self.remaining == 0 {
+      return Ok(None);
+    }
+
+    self.remaining -= 1;
+
+    Ok(Some(LightKeyframe {
+      frame: self.read.read_u32::<LE>()?,
+      color: self.read.read_vec3::<C>()?,
+      direction: self.read.read_vec3::<C>()?,
+    }))
+  }
+
+  pub fn iter<C>(&mut self) -> LightKeyframeIterator<'_, R, C> {
+    LightKeyframeIterator {
+      reader: self,
+      phantom: PhantomData,
+    }
+  }
+}
+
+pub struct LightKeyframeIterator<'a, R, C = DefaultConfig> {
+  reader: &'a mut LightKeyframeReader<R>,
+  phantom: PhantomData<C>,
+}
+
+impl<R: Read, C: Config> Iterator for LightKeyframeIterator<'_, R, C> {
+  type Item = Result<LightKeyframe<C>>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    self
+      .reader
+      .next()
+      .map_or_else(|e| Some(Err(e)), |v| v.map(Ok))
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    (
+      self.reader.remaining as usize,
+      Some(self.reader.remaining as usize),
+    )
+  }
+}
+
+impl<R: Read, C: Config> ExactSizeIterator for LightKeyframeIterator<'_, R, C> {
+  fn len(&self) -> usize {
+    self.reader.remaining as usize
+  }
+}
@@ -0,0 +1,90 @@
+use crate::{
+  shift_jis::ReadShiftJis,
+  vmd::morph::*,
+  vmd::reader::{helpers::VmdReadHelpers, BoneKeyframeReader},
+  DefaultConfig, Error, Result,
+};
+use byteorder::{ReadBytesExt, LE};
+use std::io::Read;
+
+pub struct MorphKeyframeReader<R> {
+  pub count: u32,
+  pub remaining: u32,
+  pub(crate) read: R,
+  pub(crate) poison: bool,
+}
+
+impl<R: Read> MorphKeyframeReader<R> {
+  pub fn new(mut b: BoneKeyframeReader<R>) -> Result<MorphKeyframeReader<R>> {
+    if b.poison {
+      return Err(Error::Poisoned);
+    }
+    while b.remaining > 0 {
+      b.next::<DefaultConfig>()?;
+    }
+    let count = b.read.read_count()?;
+
+    Ok(MorphKeyframeReader {
+      count,
+      remaining: count,
+      read: b.read,
+      poison: false,
+    })
+  }
+
+  pub fn next(&mut self) -> Result<Option<MorphKeyframe>> {
+    if self.poison {
+      return Err(Error::Poisoned);
+    }
+    let result = self.next_impl();
+    if result.is_err() {
+      self.poison = true;
+    }
+    result
+  }
+
+  fn next_impl(&mut self) -> Result<Option<MorphKeyframe>> {
+    if self.remaining == 0 {
+      return Ok(None);
+    }
+
+    self.remaining -= 1;
+
+    Ok(Some(MorphKeyframe {
+      morph_name: self.read.read_shift_jis(15)?,
+      frame: self.read.read_u32::<LE>()?,
+      weight: self.read.read_f32::<LE>()?,
+    }))
+  }
+
+  pub fn iter(&mut self) -> MorphKeyframeIterator<'_, R> {
+    MorphKeyframeIterator { reader: self }
+  }
+}
+pub struct MorphKeyframeIterator<'a, R> {
+  reader: &'a mut MorphKeyframeReader<R>,
+}
+
+impl<R: Read> Iterator for MorphKeyframeIterator<'_, R> {
+  type Item = Result<MorphKeyframe>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    self
+      .reader
+      .next()
+      .map_or_else(|e| Some(Err(e)), |v| v.map(Ok))
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    (
+      self.reader.remaining as usize,
+      Some(self.reader.remaining as usize),
+    )
+  }
+}
+
+impl<R: Read> ExactSizeIterator for MorphKeyframeIterator<'_, R> {
+  fn len(&self) -> usize {
+    self.reader.remaining as usize
+  }
+}
@@ -0,0 +1,91 @@
+use crate::{
+  vmd::reader::{helpers::VmdReadHelpers, LightKeyframeReader},
+  vmd::shadow::*,
+  DefaultConfig, Error, Result,
+};
+use byteorder::{ReadBytesExt, LE};
+use std::convert::TryFrom;
+use std::io::Read;
+
+pub struct ShadowKeyframeReader<R> {
+  pub count: u32,
+  pub remaining: u32,
+  pub(crate) read: R,
+  pub(crate) poison: bool,
+}
+
+impl<R: Read> ShadowKeyframeReader<R> {
+  pub fn new(mut l: LightKeyframeReader<R>) -> Result<ShadowKeyframeReader<R>> {
+    if l.poison {
+      return Err(Error::Poisoned);
+    }
+    while l.remaining > 0 {
+      l.next::<DefaultConfig>()?;
+    }
+    let count = l.read.read_count()?;
+
+    Ok(ShadowKeyframeReader {
+      count,
+      remaining: count,
+      read: l.read,
+      poison: false,
+    })
+  }
+
+  pub fn next(&mut self) -> Result<Option<ShadowKeyframe>> {
+    if self.poison {
+      return Err(Error::Poisoned);
+    }
+    let result = self.next_impl();
+    if result.is_err() {
+      self.poison = true;
+    }
+    result
+  }
+
+  fn next_impl(&mut self) -> Result<Option<ShadowKeyframe>> {
+    if self.remaining == 0 {
+      return Ok(None);
+    }
+
+    self.remaining -= 1;
+
+    Ok(Some(ShadowKeyframe {
+      frame: self.read.read_u32::<LE>()?,
+      mode: ShadowMode::try_from(self.read.read_u8()?)?,
+      distance: self.read.read_f32::<LE>()?,
+    }))
+  }
+
+  pub fn iter(&mut self) -> ShadowKeyframeIterator<'_, R> {
+    ShadowKeyframeIterator { reader: self }
+  }
+}
+
+pub struct ShadowKeyframeIterator<'a, R> {
+  reader: &'a mut ShadowKeyframeReader<R>,
+}
+
+impl<R: Read> Iterator for ShadowKeyframeIterator<'_, R> {
+  type Item = Result<ShadowKeyframe>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    self
+      .reader
+      .next()
+      .map_or_else(|e| Some(Err(e)), |v| v.map(Ok))
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    (
+      self.reader.remaining as usize,
+      Some(self.reader.remaining as usize),
+    )
+  }
+}
+
+impl<R: Read> ExactSizeIterator for ShadowKeyframeIterator<'_, R> {
+  fn len(&self) -> usize {
+    self.reader.remaining as usize
+  }
+}
@@ -0,0 +1,51 @@
+use crate::Error;
+use std::convert::TryFrom;
+use std::fmt::{Display, Formatter};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum ShadowMode {
+  Off = 0,
+  Mode1 = 1,
+  Mode2 = 2,
+}
+
+impl Display for ShadowMode {
+  fn fmt(&self, f: &mut Formatter) -> Result<(), std::fmt::Error> {
+    match self {
+      ShadowMode::Off => write!(f, "off"),
+      ShadowMode::Mode1 => write!(f, "mode 1"),
+      ShadowMode::Mode2 => write!(f, "mode 2"),
+    }
+  }
+}
+
+impl TryFrom<u8> for ShadowMode {
+  type Error = Error;
+
+  fn try_from(value: u8) -> Result<Self, Self::Error> {
+    Ok(match value {
+      0 => ShadowMode::Off,
+      1 => ShadowMode::Mode1,
+      2 => ShadowMode::Mode2,
+      e => return Err(Error::InvalidShadowMode(e)),
+    })
+  }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ShadowKeyframe {
+  pub frame: u32,
+  pub mode: ShadowMode,
+  pub distance: f32,
+}
+
+impl Display for ShadowKeyframe {
+  fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+    write!(
+      f,
+      "frame: {}, mode: {}, distance: {}",
+      self.frame, self.mode, self.distance
+    )
+  }
+}
@@ -0,0 +1,15 @@
+pub mod bone;
+pub mod camera;
+pub mod header;
+pub mod ik;
+pub mod light;
+pub mod morph;
+pub mod shadow;
+
+pub use bone::BoneKeyframeWriter;
+pub use camera::CameraKeyframeWriter;
+pub use header::HeaderWriter;
+pub use ik::IkKeyframeWriter;
+pub use light::LightKeyframeWriter;
+pub use morph::MorphKeyframeWriter;
+pub use shadow::ShadowKeyframeWriter;
@@ -0,0 +1,53 @@
+use crate::{
+  shift_jis::WriteShiftJis, vmd::bone::*, vmd::writer::HeaderWriter, writer::helpers::WriteHelpers,
+  Config, Error, Result,
+};
+use byteorder::{WriteBytesExt, LE};
+use std::io::Write;
+
+pub struct BoneKeyframeWriter<W> {
+  pub count: u32,
+  pub remaining: u32,
+  pub(crate) write: W,
+  pub(crate) poison: bool,
+}
+
+impl<W: Write> BoneKeyframeWriter<W> {
+  pub fn new(header: HeaderWriter<W>, count: u32) -> Result<BoneKeyframeWriter<W>> {
+    let mut write = header.finish()?;
+    write.write_u32::<LE>(count)?;
+
+    Ok(BoneKeyframeWriter {
+      count,
+      remaining: count,
+      write,
+      poison: false,
+    })
+  }
+
+  pub fn write<C: Config>(&mut self, keyframe: &BoneKeyframe<C>) -> Result<()> {
+    if self.poison {
+      return Err(Error::Poisoned);
+    }
+    let result = self.write_impl::<C>(keyframe);
+    if result.is_err() {
+      self.poison = true;
+    }
+    result
+  }
+
+  fn write_impl<C: Config>(&mut self, keyframe: &BoneKeyframe<C>) -> Result<()> {
+    if self.remaining == 0 {
+      return Err(Error::SectionOverflow(self.count as i32));
+    }
+
+    self.write.write_shift_jis(&keyframe.bone_name, 15)?;
+    self.write.write_u32::<LE>(keyframe.frame)?;
+    self.write.write_vec(&keyframe.translation)?;
+    self.write.write_vec(&keyframe.rotation)?;
+    self.write.write_all(&keyframe.interpolation.to_bytes())?;
+
+    self.remaining -= 1;
+    Ok(())
+  }
+}
@@ -0,0 +1,61 @@
+use crate::{
+  vmd::camera::*, vmd::writer::MorphKeyframeWriter, writer::helpers::WriteHelpers, Config, Error,
+  Result,
+};
+use byteorder::{WriteBytesExt, LE};
+use std::io::Write;
+
+pub struct CameraKeyframeWriter<W> {
+  pub count: u32,
+  pub remaining: u32,
+  pub(crate) write: W,
+  pub(crate) poison: bool,
+}
+
+impl<W: Write> CameraKeyframeWriter<W> {
+  pub fn new(m: MorphKeyframeWriter<W>, count: u32) -> Result<CameraKeyframeWriter<W>> {
+    if m.poison {
+      return Err(Error::Poisoned);
+    }
+    if m.remaining > 0 {
+      return Err(Error::SectionIncomplete(m.remaining as i32));
+    }
+    let mut write = m.write;
+    write.write_u32::<LE>(count)?;
+
+    Ok(CameraKeyframeWriter {
+      count,
+      remaining: count,
+      write,
+      poison: false,
+    })
+  }
+
+  pub fn write<C: Config>(&mut self, keyframe: &CameraKeyframe<C>) -> Result<()> {
+    if self.poison {
+      return Err(Error::Poisoned);
+    }
+    let result = self.write_impl::<C>(keyframe);
+    if result.is_err() {
+      self.poison = true;
+    }
+    result
+  }
+
+  fn write_impl<C: Config>(&mut self, keyframe: &CameraKeyframe<C>) -> Result<()> {
+    if self.remaining == 0 {
+      return Err(Error::SectionOverflow(self.count as i32));
+    }
+
+    self.write.write_u32::<LE>(keyframe.frame)?;
+    self.write.write_f32::<LE>(keyframe.distance)?;
+    self.write.write_vec(&keyframe.position)?;
+    self.write.write_vec(&keyframe.rotation)?;
+    self.write.write_all(&keyframe.interpolation.to_bytes())?;
+    self.write.write_u32::<LE>(keyframe.fov)?;
+    self.write.write_u8(!keyframe.perspective as u8)?;
+
+    self.remaining -= 1;
+    Ok(())
+  }
+}
@@ -0,0 +1,24 @@
+use crate::{shift_jis::WriteShiftJis, Result};
+use std::io::Write;
+
+pub struct HeaderWriter<W> {
+  pub model_name: String,
+  pub(crate) write: W,
+}
+
+impl<W: Write> HeaderWriter<W> {
+  pub fn new(write: W) -> HeaderWriter<W> {
+    HeaderWriter {
+      model_name: String::new(),
+      write,
+    }
+  }
+
+  pub(crate) fn finish(mut self) -> Result<W> {
+    self
+      .write
+      .write_shift_jis("Vocaloid Motion Data 0002", 30)?;
+    self.write.write_shift_jis(&self.model_name, 20)?;
+    Ok(self.write)
+  }
+}
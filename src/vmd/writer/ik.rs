@@ -0,0 +1,74 @@
+use crate::{
+  shift_jis::WriteShiftJis, vmd::ik::*, vmd::writer::ShadowKeyframeWriter, Error, Result,
+};
+use byteorder::{WriteBytesExt, LE};
+use std::io::Write;
+
+pub struct IkKeyframeWriter<W> {
+  pub count: u32,
+  pub remaining: u32,
+  pub(crate) write: W,
+  pub(crate) poison: bool,
+}
+
+impl<W: Write> IkKeyframeWriter<W> {
+  pub fn new(s: ShadowKeyframeWriter<W>, count: u32) -> Result<IkKeyframeWriter<W>> {
+    if s.poison {
+      return Err(Error::Poisoned);
+    }
+    if s.remaining > 0 {
+      return Err(Error::SectionIncomplete(s.remaining as i32));
+    }
+    let mut write = s.write;
+    write.write_u32::<LE>(count)?;
+
+    Ok(IkKeyframeWriter {
+      count,
+      remaining: count,
+      write,
+      poison: false,
+    })
+  }
+
+  pub fn write(&mut self, keyframe: &IkKeyframe) -> Result<()> {
+    if self.poison {
+      return Err(Error::Poisoned);
+    }
+    let result = self.write_impl(keyframe);
+    if result.is_err() {
+      self.poison = true;
+    }
+    result
+  }
+
+  fn write_impl(&mut self, keyframe: &IkKeyframe) -> Result<()> {
+    if self.remaining == 0 {
+      return Err(Error::SectionOverflow(self.count as i32));
+    }
+
+    self.write.write_u32::<LE>(keyframe.frame)?;
+    self.write.write_u8(keyframe.visible as u8)?;
+    self
+      .write
+      .write_u32::<LE>(keyframe.ik_states.len() as u32)?;
+
+    for state in &keyframe.ik_states {
+      self.write.write_shift_jis(&state.bone_name, 20)?;
+      self.write.write_u8(state.enabled as u8)?;
+    }
+
+    self.remaining -= 1;
+    Ok(())
+  }
+
+  pub fn finish(mut self) -> Result<W> {
+    if self.poison {
+      return Err(Error::Poisoned);
+    }
+    if self.remaining > 0 {
+      return Err(Error::SectionIncomplete(self.remaining as i32));
+    }
+    self.write.flush()?;
+    Ok(self.write)
+  }
+}
@@ -0,0 +1,57 @@
+use crate::{
+  vmd::light::*, vmd::writer::CameraKeyframeWriter, writer::helpers::WriteHelpers, Config, Error,
+  Result,
+};
+use byteorder::{WriteBytesExt, LE};
+use std::io::Write;
+
+pub struct LightKeyframeWriter<W> {
+  pub count: u32,
+  pub remaining: u32,
+  pub(crate) write: W,
+  pub(crate) poison: bool,
+}
+
+impl<W: Write> LightKeyframeWriter<W> {
+  pub fn new(c: CameraKeyframeWriter<W>, count: u32) -> Result<LightKeyframeWriter<W>> {
+    if c.poison {
+      return Err(Error::Poisoned);
+    }
+    if c.remaining > 0 {
+      return Err(Error::SectionIncomplete(c.remaining as i32));
+    }
+    let mut write = c.write;
+    write.write_u32::<LE>(count)?;
+
+    Ok(LightKeyframeWriter {
+      count,
+      remaining: count,
+      write,
+      poison: false,
+    })
+  }
+
+  pub fn write<C: Config>(&mut self, keyframe: &LightKeyframe<C>) -> Result<()> {
+    if self.poison {
+      return Err(Error::Poisoned);
+    }
+    let result = self.write_impl::<C>(keyframe);
+    if result.is_err() {
+      self.poison = true;
+    }
+    result
+  }
+
+  fn write_impl<C: Config>(&mut self, keyframe: &LightKeyframe<C>) -> Result<()> {
+    if self.remaining == 0 {
+      return Err(Error::SectionOverflow(self.count as i32));
+    }
+
+    self.write.write_u32::<LE>(keyframe.frame)?;
+    self.write.write_vec(&keyframe.color)?;
+    self.write.write_vec(&keyframe.direction)?;
+
+    self.remaining -= 1;
+    Ok(())
+  }
+}
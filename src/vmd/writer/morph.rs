@@ -0,0 +1,56 @@
+use crate::{
+  shift_jis::WriteShiftJis, vmd::morph::*, vmd::writer::BoneKeyframeWriter, Error, Result,
+};
+use byteorder::{WriteBytesExt, LE};
+use std::io::Write;
+
+pub struct MorphKeyframeWriter<W> {
+  pub count: u32,
+  pub remaining: u32,
+  pub(crate) write: W,
+  pub(crate) poison: bool,
+}
+
+impl<W: Write> MorphKeyframeWriter<W> {
+  pub fn new(b: BoneKeyframeWriter<W>, count: u32) -> Result<MorphKeyframeWriter<W>> {
+    if b.poison {
+      return Err(Error::Poisoned);
+    }
+    if b.remaining > 0 {
+      return Err(Error::SectionIncomplete(b.remaining as i32));
+    }
+    let mut write = b.write;
+    write.write_u32::<LE>(count)?;
+
+    Ok(MorphKeyframeWriter {
+      count,
+      remaining: count,
+      write,
+      poison: false,
+    })
+  }
+
+  pub fn write(&mut self, keyframe: &MorphKeyframe) -> Result<()> {
+    if self.poison {
+      return Err(Error::Poisoned);
+    }
+    let result = self.write_impl(keyframe);
+    if result.is_err() {
+      self.poison = true;
+    }
+    result
+  }
+
+  fn write_impl(&mut self, keyframe: &MorphKeyframe) -> Result<()> {
+    if self.remaining == 0 {
+      return Err(Error::SectionOverflow(self.count as i32));
+    }
+
+    self.write.write_shift_jis(&keyframe.morph_name, 15)?;
+    self.write.write_u32::<LE>(keyframe.frame)?;
+    self.write.write_f32::<LE>(keyframe.weight)?;
+
+    self.remaining -= 1;
+    Ok(())
+  }
+}
@@ -0,0 +1,54 @@
+use crate::{vmd::shadow::*, vmd::writer::LightKeyframeWriter, Error, Result};
+use byteorder::{WriteBytesExt, LE};
+use std::io::Write;
+
+pub struct ShadowKeyframeWriter<W> {
+  pub count: u32,
+  pub remaining: u32,
+  pub(crate) write: W,
+  pub(crate) poison: bool,
+}
+
+impl<W: Write> ShadowKeyframeWriter<W> {
+  pub fn new(l: LightKeyframeWriter<W>, count: u32) -> Result<ShadowKeyframeWriter<W>> {
+    if l.poison {
+      return Err(Error::Poisoned);
+    }
+    if l.remaining > 0 {
+      return Err(Error::SectionIncomplete(l.remaining as i32));
+    }
+    let mut write = l.write;
+    write.write_u32::<LE>(count)?;
+
+    Ok(ShadowKeyframeWriter {
+      count,
+      remaining: count,
+      write,
+      poison: false,
+    })
+  }
+
+  pub fn write(&mut self, keyframe: &ShadowKeyframe) -> Result<()> {
+    if self.poison {
+      return Err(Error::Poisoned);
+    }
+    let result = self.write_impl(keyframe);
+    if result.is_err() {
+      self.poison = true;
+    }
+    result
+  }
+
+  fn write_impl(&mut self, keyframe: &ShadowKeyframe) -> Result<()> {
+    if self.remaining == 0 {
+      return Err(Error::SectionOverflow(self.count as i32));
+    }
+
+    self.write.write_u32::<LE>(keyframe.frame)?;
+    self.write.write_u8(keyframe.mode as u8)?;
+    self.write.write_f32::<LE>(keyframe.distance)?;
+
+    self.remaining -= 1;
+    Ok(())
+  }
+}
@@ -0,0 +1,7 @@
+pub mod pose;
+pub mod reader;
+pub mod writer;
+
+pub use pose::{BonePose, MorphPose, Pose};
+pub use reader::{from_path, read};
+pub use writer::write;
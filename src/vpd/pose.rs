@@ -0,0 +1,80 @@
+use crate::{pmx::morph::Morph, Bone, Config};
+use itertools::Itertools;
+use std::fmt::{Display, Formatter};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BonePose<C: Config> {
+  pub bone_name: String,
+  pub translation: C::Vec3,
+  pub rotation: C::Vec4,
+}
+
+impl<C: Config> Display for BonePose<C>
+where
+  C::Vec3: Display,
+  C::Vec4: Display,
+{
+  fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+    write!(
+      f,
+      "{} move {} rotate {}",
+      self.bone_name, self.translation, self.rotation
+    )
+  }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct MorphPose {
+  pub morph_name: String,
+  pub weight: f32,
+}
+
+impl Display for MorphPose {
+  fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+    write!(f, "{} at {}", self.morph_name, self.weight)
+  }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Pose<C: Config> {
+  pub model_name: String,
+  pub bones: Vec<BonePose<C>>,
+  pub morphs: Vec<MorphPose>,
+}
+
+impl<C: Config> Pose<C> {
+  pub fn bone(&self, name: &str) -> Option<&BonePose<C>> {
+    self.bones.iter().find(|b| b.bone_name == name)
+  }
+
+  pub fn morph(&self, name: &str) -> Option<&MorphPose> {
+    self.morphs.iter().find(|m| m.morph_name == name)
+  }
+
+  pub fn resolve_bones(&self, bones: &[Bone<C>]) -> Vec<Option<&BonePose<C>>> {
+    bones.iter().map(|b| self.bone(&b.local_name)).collect()
+  }
+
+  pub fn resolve_morphs(&self, morphs: &[Morph<C>]) -> Vec<f32> {
+    morphs
+      .iter()
+      .map(|m| self.morph(&m.local_name).map_or(0.0, |m| m.weight))
+      .collect()
+  }
+}
+
+impl<C: Config> Display for Pose<C>
+where
+  C::Vec3: Display,
+  C::Vec4: Display,
+{
+  fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+    write!(
+      f,
+      "model: {}\nbones:\n{}\nmorphs:\n{}",
+      self.model_name,
+      self.bones.iter().map(ToString::to_string).join("\n"),
+      self.morphs.iter().map(ToString::to_string).join("\n")
+    )
+  }
+}
@@ -0,0 +1,105 @@
+use crate::{
+  vpd::pose::{BonePose, MorphPose, Pose},
+  Config, Error, Result,
+};
+use encoding::all::WINDOWS_31J;
+use encoding::{DecoderTrap, Encoding};
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::Path;
+
+pub fn read<C: Config, R: Read>(mut read: R) -> Result<Pose<C>> {
+  let mut buf = Vec::new();
+  read.read_to_end(&mut buf)?;
+  let text = WINDOWS_31J
+    .decode(&buf, DecoderTrap::Strict)
+    .map_err(Error::DecodeText)?;
+
+  parse(&text)
+}
+
+pub fn from_path<C: Config, P: AsRef<Path>>(path: P) -> Result<Pose<C>> {
+  read(BufReader::new(File::open(path)?))
+}
+
+fn parse<C: Config>(text: &str) -> Result<Pose<C>> {
+  let last_line = text.lines().count();
+  let mut lines = text
+    .lines()
+    .enumerate()
+    .map(|(i, line)| {
+      let line = line.find("//").map_or(line, |comment| &line[..comment]);
+      (i + 1, line.trim())
+    })
+    .filter(|(_, line)| !line.is_empty());
+
+  let signature = lines.next().map_or("", |(_, line)| line);
+  if signature != "Vocaloid Pose Data file" {
+    return Err(Error::WrongVpdSignature(signature.to_owned()));
+  }
+
+  let mut statement = || lines.next().ok_or(Error::InvalidVpdSyntax(last_line));
+  let model_name = value(statement()?)?.to_owned();
+  let (number, line) = statement()?;
+  value((number, line))?
+    .parse::<usize>()
+    .map_err(|_| Error::InvalidVpdSyntax(number))?;
+
+  let mut pose = Pose {
+    model_name,
+    bones: vec![],
+    morphs: vec![],
+  };
+
+  while let Some((number, line)) = lines.next() {
+    let (kind, name) = line.split_at(line.find('{').ok_or(Error::InvalidVpdSyntax(number))?);
+    let name = name[1..].trim().to_owned();
+
+    let mut statement = || lines.next().ok_or(Error::InvalidVpdSyntax(last_line));
+    if kind.starts_with("Bone") {
+      let translation = floats(statement()?)?;
+      let rotation = floats(statement()?)?;
+      if translation.len() != 3 || rotation.len() != 4 {
+        return Err(Error::InvalidVpdSyntax(number));
+      }
+      pose.bones.push(BonePose {
+        bone_name: name,
+        translation: [translation[0], translation[1], translation[2]].into(),
+        rotation: [rotation[0], rotation[1], rotation[2], rotation[3]].into(),
+      });
+    } else if kind.starts_with("Morph") {
+      let weight = floats(statement()?)?;
+      if weight.len() != 1 {
+        return Err(Error::InvalidVpdSyntax(number));
+      }
+      pose.morphs.push(MorphPose {
+        morph_name: name,
+        weight: weight[0],
+      });
+    } else {
+      return Err(Error::InvalidVpdSyntax(number));
+    }
+
+    let (number, line) = statement()?;
+    if line != "}" {
+      return Err(Error::InvalidVpdSyntax(number));
+    }
+  }
+
+  Ok(pose)
+}
+
+fn value((number, line): (usize, &str)) -> Result<&str> {
+  line
+    .strip_suffix(';')
+    .map(str::trim)
+    .ok_or(Error::InvalidVpdSyntax(number))
+}
+
+fn floats((number, line): (usize, &str)) -> Result<Vec<f32>> {
+  value((number, line))?
+    .split(',')
+    .map(|v| v.trim().parse::<f32>())
+    .collect::<std::result::Result<_, _>>()
+    .map_err(|_| Error::InvalidVpdSyntax(number))
+}
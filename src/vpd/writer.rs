@@ -0,0 +1,53 @@
+use crate::{vpd::pose::Pose, Config, Error, Result};
+use encoding::all::WINDOWS_31J;
+use encoding::{EncoderTrap, Encoding};
+use std::fmt::Write as _;
+use std::io::Write;
+
+pub fn write<C: Config, W: Write>(pose: &Pose<C>, mut write: W) -> Result<W> {
+  let mut text = String::new();
+  // writing into a String can not fail
+  let _ = write_text(pose, &mut text);
+
+  let buf = WINDOWS_31J
+    .encode(&text, EncoderTrap::Strict)
+    .map_err(Error::EncodeText)?;
+  write.write_all(&buf)?;
+  write.flush()?;
+  Ok(write)
+}
+
+fn write_text<C: Config>(pose: &Pose<C>, text: &mut String) -> std::fmt::Result {
+  write!(text, "Vocaloid Pose Data file\r\n\r\n")?;
+  write!(text, "{};\t\t// 親ファイル名\r\n", pose.model_name)?;
+  write!(
+    text,
+    "{};\t\t\t\t// 総ポーズボーン数\r\n\r\n",
+    pose.bones.len()
+  )?;
+
+  for (i, bone) in pose.bones.iter().enumerate() {
+    let t = bone.translation.as_ref();
+    let r = bone.rotation.as_ref();
+    write!(text, "Bone{}{{{}\r\n", i, bone.bone_name)?;
+    write!(
+      text,
+      "  {:.6},{:.6},{:.6};\t\t\t\t// trans x,y,z\r\n",
+      t[0], t[1], t[2]
+    )?;
+    write!(
+      text,
+      "  {:.6},{:.6},{:.6},{:.6};\t\t// Quaternion x,y,z,w\r\n",
+      r[0], r[1], r[2], r[3]
+    )?;
+    write!(text, "}}\r\n\r\n")?;
+  }
+
+  for (i, morph) in pose.morphs.iter().enumerate() {
+    write!(text, "Morph{}{{{}\r\n", i, morph.morph_name)?;
+    write!(text, "  {:.6};\t\t\t\t// weight\r\n", morph.weight)?;
+    write!(text, "}}\r\n\r\n")?;
+  }
+
+  Ok(())
+}
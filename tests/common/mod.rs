@@ -0,0 +1,436 @@
+// each test crate includes this module and uses only part of it
+#![allow(dead_code)]
+
+use mmd::pmx::bone::*;
+use mmd::pmx::display::*;
+use mmd::pmx::joint::*;
+use mmd::pmx::material::*;
+use mmd::pmx::morph::*;
+use mmd::pmx::rigid_body::*;
+use mmd::pmx::soft_body::*;
+use mmd::pmx::weight_deform::*;
+use mmd::{DefaultConfig, ExtraGlobals, IndexSize, Model, Settings, TextEncoding, Vertex};
+use std::convert::TryFrom;
+
+// a small model with every section and every variant the format can store, indexed so it still fits
+// the smallest index size
+pub fn model(text_encoding: TextEncoding, index_size: IndexSize) -> Model<DefaultConfig> {
+  let settings = Settings {
+    text_encoding,
+    additional_vec4_count: 4,
+    vertex_index_size: index_size,
+    texture_index_size: index_size,
+    material_index_size: index_size,
+    bone_index_size: index_size,
+    morph_index_size: index_size,
+    rigidbody_index_size: index_size,
+    extra_globals: ExtraGlobals::try_from(vec![7, 9]).unwrap(),
+  };
+
+  let weight_deforms = vec![
+    WeightDeform::Bdef1(Bdef1 { bone_index: 0 }),
+    WeightDeform::Bdef2(Bdef2 {
+      bone_1_index: 0,
+      bone_2_index: 1,
+      bone_1_weight: 0.25,
+    }),
+    WeightDeform::Bdef4(Bdef4 {
+      bone_1_index: 0,
+      bone_2_index: 1,
+      bone_3_index: 2,
+      bone_4_index: 3,
+      bone_1_weight: 0.1,
+      bone_2_weight: 0.2,
+      bone_3_weight: 0.3,
+      bone_4_weight: 0.4,
+    }),
+    WeightDeform::Sdef(Sdef {
+      bone_1_index: 1,
+      bone_2_index: 2,
+      bone_1_weight: 0.5,
+      c: [0.0, 1.0, 0.0].into(),
+      r0: [0.0, 1.5, 0.0].into(),
+      r1: [0.0, 0.5, 0.0].into(),
+    }),
+    WeightDeform::Qdef(Qdef {
+      bone_1_index: 3,
+      bone_2_index: 2,
+      bone_3_index: 1,
+      bone_4_index: 0,
+      bone_1_weight: 0.4,
+      bone_2_weight: 0.3,
+      bone_3_weight: 0.2,
+      bone_4_weight: 0.1,
+    }),
+  ];
+  let vertices = weight_deforms
+    .into_iter()
+    .enumerate()
+    .map(|(i, weight_deform)| {
+      let x = i as f32;
+      Vertex {
+        position: [x, x * 2.0, -x].into(),
+        normal: [0.0, 0.0, -1.0].into(),
+        uv: [x / 4.0, 1.0 - x / 4.0].into(),
+        additional: (0..4).map(|j| [x, j as f32, 0.5, 1.0].into()).collect(),
+        weight_deform,
+        edge_scale: 1.0 + x,
+      }
+    })
+    .collect();
+
+  let materials = vec![
+    Material {
+      local_name: "肌".to_string(),
+      universal_name: "skin".to_string(),
+      diffuse_color: [1.0, 0.8, 0.7, 1.0].into(),
+      specular_color: [0.1, 0.1, 0.1].into(),
+      specular_strength: 5.0,
+      ambient_color: [0.5, 0.4, 0.35].into(),
+      draw_flags: DrawingFlags::NoCull | DrawingFlags::HasEdge,
+      edge_color: [0.0, 0.0, 0.0, 1.0].into(),
+      edge_scale: 0.5,
+      texture_index: 0,
+      environment_index: -1,
+      environment_blend_mode: EnvironmentBlendMode::Disabled,
+      toon: Toon::Internal(3),
+      metadata: "メモ".to_string(),
+      surface_count: 3,
+    },
+    Material {
+      local_name: "髪".to_string(),
+      universal_name: "hair".to_string(),
+      diffuse_color: [0.2, 0.1, 0.1, 0.9].into(),
+      specular_color: [0.3, 0.3, 0.3].into(),
+      specular_strength: 20.0,
+      ambient_color: [0.1, 0.05, 0.05].into(),
+      draw_flags: DrawingFlags::DrawShadow | DrawingFlags::ReceiveShadow,
+      edge_color: [0.1, 0.0, 0.0, 1.0].into(),
+      edge_scale: 1.0,
+      texture_index: 1,
+      environment_index: 1,
+      environment_blend_mode: EnvironmentBlendMode::Additive,
+      toon: Toon::Texture(0),
+      metadata: String::new(),
+      surface_count: 3,
+    },
+  ];
+
+  let bone = |local_name: &str, parent: i32, position: [f32; 3]| Bone {
+    local_name: local_name.to_string(),
+    universal_name: String::new(),
+    position: position.into(),
+    parent,
+    transform_level: 0,
+    bone_flags: BoneFlags::Rotatable | BoneFlags::Display | BoneFlags::CanOperate,
+    connection: Connection::Position([0.0, -1.0, 0.0].into()),
+    additional: None,
+    fixed_axis: None,
+    local_axis: None,
+    external_parent_transform: None,
+    inverse_kinematics: None,
+  };
+  let mut bones = vec![
+    bone("センター", -1, [0.0, 8.0, 0.0]),
+    bone("ひざ", 0, [0.0, 4.0, 0.0]),
+    bone("足首", 1, [0.0, 1.0, 0.0]),
+    bone("足ＩＫ", 0, [0.0, 1.0, 0.0]),
+  ];
+  bones[0].bone_flags |= BoneFlags::Movable | BoneFlags::Connection;
+  bones[0].connection = Connection::Index(1);
+  bones[1].bone_flags |= BoneFlags::AddRotation | BoneFlags::FixedAxis | BoneFlags::LocalAxis;
+  bones[1].additional = Some(Additional {
+    parent: 0,
+    rate: 0.5,
+  });
+  bones[1].fixed_axis = Some([1.0, 0.0, 0.0].into());
+  bones[1].local_axis = Some(LocalAxis {
+    x: [1.0, 0.0, 0.0].into(),
+    z: [0.0, 0.0, 1.0].into(),
+  });
+  bones[2].bone_flags |= BoneFlags::ExternalParentTransform;
+  bones[2].external_parent_transform = Some(2);
+  bones[3].bone_flags |= BoneFlags::InverseKinematics | BoneFlags::Movable;
+  bones[3].transform_level = 1;
+  bones[3].inverse_kinematics = Some(InverseKinematics {
+    ik_bone: 2,
+    iterations: 40,
+    limit_angle: 2.0,
+    links: vec![
+      IKLink {
+        ik_bone: 1,
+        limits: Some(([-3.0, 0.0, 0.0].into(), [-0.01, 0.0, 0.0].into())),
+      },
+      IKLink {
+        ik_bone: 0,
+        limits: None,
+      },
+    ],
+  });
+
+  let uv = |vertex: i32| UVOffset {
+    vertex,
+    offset: [0.1, 0.2, 0.3, 0.4].into(),
+  };
+  let group = || {
+    vec![
+      GroupOffset {
+        morph: 0,
+        influence: 0.5,
+      },
+      GroupOffset {
+        morph: 1,
+        influence: 1.0,
+      },
+    ]
+  };
+  let offsets = vec![
+    Offsets::Vertex(vec![VertexOffset {
+      vertex: 4,
+      offset: [0.0, 0.1, 0.0].into(),
+    }]),
+    Offsets::Bone(vec![BoneOffset {
+      bone: 2,
+      translation: [0.0, 0.5, 0.0].into(),
+      rotation: [0.0, 0.0, 0.38, 0.92].into(),
+    }]),
+    Offsets::UV(vec![uv(0)]),
+    Offsets::AdditionalUV1(vec![uv(1)]),
+    Offsets::AdditionalUV2(vec![uv(2)]),
+    Offsets::AdditionalUV3(vec![uv(3)]),
+    Offsets::AdditionalUV4(vec![uv(4)]),
+    Offsets::Material(vec![MaterialOffset {
+      material: -1,
+      method: OffsetMethod::Multiply,
+      diffuse_color: [1.0, 0.5, 0.5, 1.0].into(),
+      specular_color: [1.0, 1.0, 1.0].into(),
+      specular_strength: 1.0,
+      ambient_color: [1.0, 1.0, 1.0].into(),
+      edge_color: [1.0, 1.0, 1.0, 1.0].into(),
+      edge_scale: 1.0,
+      texture_tint: [1.0, 1.0, 1.0, 1.0].into(),
+      environment_tint: [1.0, 1.0, 1.0, 1.0].into(),
+      toon_tint: [1.0, 1.0, 1.0, 1.0].into(),
+    }]),
+    Offsets::Impulse(vec![ImpulseOffset {
+      rigid_body: 1,
+      local: true,
+      velocity: [0.0, 1.0, 0.0].into(),
+      torque: [0.0, 0.0, 0.5].into(),
+    }]),
+    Offsets::Group(group()),
+    Offsets::Flip(group()),
+  ];
+  let panels = [
+    Panel::Hidden,
+    Panel::Eyebrows,
+    Panel::Eyes,
+    Panel::Mouth,
+    Panel::Other,
+    Panel::Unknown(7),
+  ];
+  let morphs = offsets
+    .into_iter()
+    .enumerate()
+    .map(|(i, offsets)| Morph {
+      local_name: format!("モーフ{}", i),
+      universal_name: format!("morph {}", i),
+      panel: panels[i % panels.len()],
+      offsets,
+    })
+    .collect();
+
+  let display_frames = vec![
+    DisplayFrame {
+      local_name: "Root".to_string(),
+      universal_name: "Root".to_string(),
+      special_flag: true,
+      frames: vec![Frame::Bone(0)],
+    },
+    DisplayFrame {
+      local_name: "表情".to_string(),
+      universal_name: "Exp".to_string(),
+      special_flag: false,
+      frames: vec![Frame::Morph(0), Frame::Bone(3), Frame::Morph(10)],
+    },
+  ];
+
+  let rigid_body = |local_name: &str, bone_index: i32, shape, physics_mode| RigidBody {
+    local_name: local_name.to_string(),
+    universal_name: String::new(),
+    bone_index,
+    group_id: 1,
+    collision_mask: 0xfffe,
+    shape,
+    shape_size: [0.5, 1.0, 0.5].into(),
+    shape_position: [0.0, 6.0, 0.0].into(),
+    shape_rotation: [0.0, 0.0, 0.1].into(),
+    mass: 1.0,
+    move_attenuation: 0.5,
+    rotation_damping: 0.5,
+    repulsion: 0.1,
+    fiction: 0.5,
+    physics_mode,
+  };
+  let rigid_bodies = vec![
+    rigid_body("上", 0, ShapeType::Box, PhysicsMode::Static),
+    rigid_body("中", 1, ShapeType::Capsule, PhysicsMode::Dynamic),
+    rigid_body("下", -1, ShapeType::Sphere, PhysicsMode::DynamicPivoted),
+  ];
+
+  let joint = |rigid_body_a: i32, rigid_body_b: i32, joint_type| Joint {
+    local_name: format!("{}-{}", rigid_body_a, rigid_body_b),
+    universal_name: String::new(),
+    joint_type,
+    rigid_body_a,
+    rigid_body_b,
+    position: [0.0, 5.0, 0.0].into(),
+    rotation: [0.0, 0.0, 0.0].into(),
+    position_min: [-0.1, -0.1, -0.1].into(),
+    position_max: [0.1, 0.1, 0.1].into(),
+    rotation_min: [-0.5, -0.5, -0.5].into(),
+    rotation_max: [0.5, 0.5, 0.5].into(),
+    position_spring: [0.0, 0.0, 0.0].into(),
+    rotation_spring: [10.0, 10.0, 10.0].into(),
+  };
+  let joints = vec![
+    joint(0, 1, JointType::SpringFree),
+    joint(1, 2, JointType::Hinge),
+  ];
+
+  let soft_bodies = vec![SoftBody {
+    local_name: "布".to_string(),
+    universal_name: "cloth".to_string(),
+    shape: SoftBodyShape::TriMesh,
+    material_index: 1,
+    group_id: 3,
+    collision_mask: 0xfff0,
+    flags: SoftBodyFlags::BLink | SoftBodyFlags::LinkCrossing,
+    b_link_distance: 2,
+    cluster_count: 4,
+    total_mass: 1.5,
+    collision_margin: 0.05,
+    aero_model: AeroModel::FaceOneSided,
+    config: SoftBodyConfig {
+      damping: 0.1,
+      anchor_hardness: 0.9,
+      ..Default::default()
+    },
+    cluster: SoftBodyCluster {
+      soft_soft_impulse_split: 0.5,
+      ..Default::default()
+    },
+    iterations: SoftBodyIterations {
+      velocity: 1,
+      position: 2,
+      drift: 3,
+      cluster: 4,
+    },
+    material: SoftBodyMaterial {
+      linear_stiffness: 1.0,
+      area_stiffness: 0.5,
+      volume_stiffness: 0.25,
+    },
+    anchors: vec![
+      Anchor {
+        rigid_body: 0,
+        vertex: 1,
+        near_mode: true,
+      },
+      Anchor {
+        rigid_body: 2,
+        vertex: 2,
+        near_mode: false,
+      },
+    ],
+    pinned_vertices: vec![0, 3],
+  }];
+
+  Model {
+    version: 2.1,
+    settings,
+    model_local_name: "テスト".to_string(),
+    model_universal_name: "test".to_string(),
+    local_comments: "コメント\r\n二行目".to_string(),
+    universal_comments: "comment".to_string(),
+    vertices,
+    surfaces: vec![[0, 1, 2], [2, 3, 4]],
+    textures: vec!["tex\\skin.png".to_string(), "髪.bmp".to_string()],
+    materials,
+    bones,
+    morphs,
+    display_frames,
+    rigid_bodies,
+    joints,
+    soft_bodies,
+  }
+}
+
+// models have no `PartialEq`, so they are compared by a description of every field
+pub fn describe(model: &Model<DefaultConfig>) -> Vec<String> {
+  let mut lines = vec![
+    format!(
+      "{} {} {} {} {}",
+      model.version,
+      model.model_local_name,
+      model.model_universal_name,
+      model.local_comments,
+      model.universal_comments
+    ),
+    model.settings.to_string(),
+  ];
+  for vertex in &model.vertices {
+    let weight_deform = match &vertex.weight_deform {
+      WeightDeform::Bdef1(d) => format!("bdef1 {}", d.bone_index),
+      WeightDeform::Bdef2(d) => format!(
+        "bdef2 {} {} {}",
+        d.bone_1_index, d.bone_2_index, d.bone_1_weight
+      ),
+      WeightDeform::Bdef4(d) => format!(
+        "bdef4 {} {} {} {} {} {} {} {}",
+        d.bone_1_index,
+        d.bone_2_index,
+        d.bone_3_index,
+        d.bone_4_index,
+        d.bone_1_weight,
+        d.bone_2_weight,
+        d.bone_3_weight,
+        d.bone_4_weight
+      ),
+      WeightDeform::Sdef(d) => format!(
+        "sdef {} {} {} {} {} {}",
+        d.bone_1_index, d.bone_2_index, d.bone_1_weight, d.c, d.r0, d.r1
+      ),
+      WeightDeform::Qdef(d) => format!(
+        "qdef {} {} {} {} {} {} {} {}",
+        d.bone_1_index,
+        d.bone_2_index,
+        d.bone_3_index,
+        d.bone_4_index,
+        d.bone_1_weight,
+        d.bone_2_weight,
+        d.bone_3_weight,
+        d.bone_4_weight
+      ),
+    };
+    lines.push(format!(
+      "{} {} {} {:?} {} {}",
+      vertex.position,
+      vertex.normal,
+      vertex.uv,
+      vertex.additional,
+      weight_deform,
+      vertex.edge_scale
+    ));
+  }
+  lines.push(format!("{:?}", model.surfaces));
+  lines.push(format!("{:?}", model.textures));
+  lines.extend(model.materials.iter().map(|m| m.to_string()));
+  lines.extend(model.bones.iter().map(|b| b.to_string()));
+  lines.extend(model.morphs.iter().map(|m| m.to_string()));
+  lines.extend(model.display_frames.iter().map(|d| d.to_string()));
+  lines.extend(model.rigid_bodies.iter().map(|r| r.to_string()));
+  lines.extend(model.joints.iter().map(|j| j.to_string()));
+  lines.extend(model.soft_bodies.iter().map(|s| s.to_string()));
+  lines
+}
@@ -0,0 +1,93 @@
+use mmd::math;
+use mmd::pmx::bone::{BoneFlags, Connection, IKLink, InverseKinematics};
+use mmd::pmx::skeleton::{Skeleton, Transform};
+use mmd::{Bone, DefaultConfig};
+
+// the limits MMD models give knees, which only bend backwards
+const KNEE: ([f32; 3], [f32; 3]) = ([-std::f32::consts::PI, 0.0, 0.0], [-0.008, 0.0, 0.0]);
+
+fn bone(parent: i32, position: [f32; 3]) -> Bone<DefaultConfig> {
+  Bone {
+    local_name: String::new(),
+    universal_name: String::new(),
+    position: position.into(),
+    parent,
+    transform_level: 0,
+    bone_flags: BoneFlags::Rotatable.into(),
+    connection: Connection::Index(-1),
+    additional: None,
+    fixed_axis: None,
+    local_axis: None,
+    external_parent_transform: None,
+    inverse_kinematics: None,
+  }
+}
+
+// a hip, knee and ankle five units apart, with the ankle pulled towards the goal by a leg IK bone
+fn solve(goal: [f32; 3]) -> (Vec<Transform>, Vec<math::Mat4>) {
+  let mut ik = bone(-1, [0.0, 0.0, 0.0]);
+  ik.bone_flags |= BoneFlags::InverseKinematics | BoneFlags::Movable;
+  ik.inverse_kinematics = Some(InverseKinematics {
+    ik_bone: 2,
+    iterations: 40,
+    limit_angle: 2.0,
+    links: vec![
+      IKLink {
+        ik_bone: 1,
+        limits: Some((KNEE.0.into(), KNEE.1.into())),
+      },
+      IKLink {
+        ik_bone: 0,
+        limits: None,
+      },
+    ],
+  });
+  let bones = vec![
+    bone(-1, [0.0, 10.0, 0.0]),
+    bone(0, [0.0, 5.0, 0.0]),
+    bone(1, [0.0, 0.0, 0.0]),
+    ik,
+  ];
+  let skeleton = Skeleton::new(&bones).unwrap();
+
+  let mut locals = vec![Transform::default(); bones.len()];
+  locals[3].translation = goal;
+  let mut world = skeleton.world(&locals);
+  skeleton.solve_ik(3, &mut locals, &mut world);
+  (locals, world)
+}
+
+fn assert_knee_limits(locals: &[Transform]) {
+  let angles = math::quat_to_euler(locals[1].rotation);
+  for axis in 0..3 {
+    assert!(
+      angles[axis] >= KNEE.0[axis] - 1e-3 && angles[axis] <= KNEE.1[axis] + 1e-3,
+      "knee rotated to {:?}",
+      angles
+    );
+  }
+}
+
+#[test]
+fn converges_on_reachable_goals() {
+  for &goal in &[[0.0, 2.0, 1.0], [0.0, 6.0, 3.0], [1.0, 3.0, 0.0]] {
+    let (locals, world) = solve(goal);
+    let ankle = math::mat4_translation(&world[2]);
+    assert!(
+      math::length(math::sub(ankle, goal)) < 1e-2,
+      "ankle at {:?} for {:?}",
+      ankle,
+      goal
+    );
+    assert_knee_limits(&locals);
+  }
+}
+
+#[test]
+fn keeps_the_knee_within_its_limits() {
+  // straight down out of reach, and in front where only bending the knee forwards would get there
+  for &goal in &[[0.0, -5.0, 0.0], [0.0, 3.0, -3.0]] {
+    let (locals, _) = solve(goal);
+    assert_knee_limits(&locals);
+  }
+}
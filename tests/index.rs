@@ -0,0 +1,92 @@
+mod common;
+
+use mmd::pmx::error::Section;
+use mmd::reader::SectionIndex;
+use mmd::{
+  BoneReader, DefaultConfig, DisplayReader, HeaderReader, IndexSize, JointReader, MaterialReader,
+  MorphReader, RigidBodyReader, SoftBodyReader, SurfaceReader, TextEncoding, TextureReader,
+  VertexReader,
+};
+use std::cell::Cell;
+use std::io::{Cursor, Read};
+use std::rc::Rc;
+
+// shares how far the readers it is moved into have got
+struct Counting<R> {
+  read: R,
+  offset: Rc<Cell<u64>>,
+}
+
+impl<R: Read> Read for Counting<R> {
+  fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+    let size = self.read.read(buf)?;
+    self.offset.set(self.offset.get() + size as u64);
+    Ok(size)
+  }
+}
+
+#[test]
+fn offsets_match_the_sequential_readers() {
+  for &version in &[2.0, 2.1] {
+    for &index_size in &[IndexSize::I8, IndexSize::I16, IndexSize::I32] {
+      let mut model = common::model(TextEncoding::UTF16LE, index_size);
+      model.version = version;
+      if version < 2.1 {
+        model.soft_bodies.clear();
+      }
+      let bytes = model.write(Vec::new()).unwrap();
+      let index = SectionIndex::scan(Cursor::new(&bytes)).unwrap();
+
+      let offset = Rc::new(Cell::new(0));
+      let read = Counting {
+        read: &bytes[..],
+        offset: offset.clone(),
+      };
+
+      // before each element the reader is at its offset, and after the last one at the section end
+      macro_rules! walk {
+        ($reader:expr, $section:expr, $($next:tt)*) => {{
+          let mut element = 0;
+          loop {
+            let expected = index
+              .element_offset($section, element)
+              .unwrap_or_else(|| index.end($section));
+            assert_eq!(offset.get(), expected, "{} #{}", $section, element);
+            if $reader.$($next)*.unwrap().is_none() {
+              break;
+            }
+            element += 1;
+          }
+          assert_eq!(element, index.count($section));
+        }};
+      }
+
+      let mut vertices = VertexReader::new(HeaderReader::new(read).unwrap()).unwrap();
+      walk!(vertices, Section::Vertices, next::<DefaultConfig>());
+      let mut surfaces = SurfaceReader::new(vertices).unwrap();
+      walk!(surfaces, Section::Surfaces, next::<DefaultConfig>());
+      let mut textures = TextureReader::new(surfaces).unwrap();
+      walk!(textures, Section::Textures, next());
+      let mut materials = MaterialReader::new(textures).unwrap();
+      walk!(materials, Section::Materials, next::<DefaultConfig>());
+      let mut bones = BoneReader::new(materials).unwrap();
+      walk!(bones, Section::Bones, next::<DefaultConfig>());
+      let mut morphs = MorphReader::new(bones).unwrap();
+      walk!(morphs, Section::Morphs, next::<DefaultConfig>());
+      let mut display_frames = DisplayReader::new(morphs).unwrap();
+      walk!(
+        display_frames,
+        Section::DisplayFrames,
+        next::<DefaultConfig>()
+      );
+      let mut rigid_bodies = RigidBodyReader::new(display_frames).unwrap();
+      walk!(rigid_bodies, Section::RigidBodies, next::<DefaultConfig>());
+      let mut joints = JointReader::new(rigid_bodies).unwrap();
+      walk!(joints, Section::Joints, next::<DefaultConfig>());
+      let mut soft_bodies = SoftBodyReader::new(joints).unwrap();
+      walk!(soft_bodies, Section::SoftBodies, next::<DefaultConfig>());
+
+      assert_eq!(index.end(Section::SoftBodies), bytes.len() as u64);
+    }
+  }
+}
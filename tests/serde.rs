@@ -0,0 +1,16 @@
+#![cfg(feature = "serde")]
+
+mod common;
+
+use mmd::{DefaultConfig, IndexSize, Model, TextEncoding};
+
+#[test]
+fn json_round_trip() {
+  // the fixture holds every weight deform, morph offset and toon variant
+  let model = common::model(TextEncoding::UTF8, IndexSize::I16);
+  let json = serde_json::to_string(&model).unwrap();
+  let read: Model<DefaultConfig> = serde_json::from_str(&json).unwrap();
+
+  assert_eq!(common::describe(&read), common::describe(&model));
+  assert_eq!(serde_json::to_string(&read).unwrap(), json);
+}
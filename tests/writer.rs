@@ -0,0 +1,23 @@
+mod common;
+
+use mmd::{DefaultConfig, IndexSize, Model, TextEncoding};
+
+#[test]
+fn round_trip() {
+  for &text_encoding in &[TextEncoding::UTF8, TextEncoding::UTF16LE] {
+    for &index_size in &[IndexSize::I8, IndexSize::I16, IndexSize::I32] {
+      let model = common::model(text_encoding, index_size);
+      let written = model.write(Vec::new()).unwrap();
+      let read = Model::<DefaultConfig>::read(&written[..]).unwrap();
+
+      assert_eq!(
+        common::describe(&read),
+        common::describe(&model),
+        "{} with {} indices",
+        text_encoding,
+        index_size
+      );
+      assert_eq!(read.write(Vec::new()).unwrap(), written);
+    }
+  }
+}